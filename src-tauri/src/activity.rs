@@ -0,0 +1,227 @@
+//! 活动时间线：按天、周、月汇总会话的创建数量、代码行数和模式分布。
+//! 会话按创建时间戳归入时间段（没有创建时间时用最后更新时间），日期按设置的时区计算。
+
+use crate::chat_mode::ChatMode;
+use crate::timezone;
+use crate::{workspace_scan, workspace_uri, AppError, ChatSession};
+use chrono::{Datelike, Duration, Months, NaiveDate};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashSet};
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum Granularity {
+    Day,
+    /// 周一开始的自然周
+    Week,
+    Month,
+}
+
+/// 时间范围，日期格式 YYYY-MM-DD，两端都包含；不指定开始时从最早的会话开始，不指定结束时到今天
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct ActivityRange {
+    pub start: Option<String>,
+    pub end: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct ActivityBucket {
+    /// 时间段第一天
+    pub start: String,
+    /// 2024-03-05、2024-W10、2024-03
+    pub label: String,
+    pub chat_count: i64,
+    pub agent_count: i64,
+    pub chat_mode_count: i64,
+    pub lines_added: i64,
+    pub lines_removed: i64,
+    pub files_changed: i64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ActivityTimeline {
+    pub granularity: Granularity,
+    pub start: Option<String>,
+    pub end: Option<String>,
+    /// 连续的时间段，没有活动的时间段也会返回
+    pub buckets: Vec<ActivityBucket>,
+    /// 没有时间信息、无法归入时间段的会话
+    pub undated_count: i64,
+}
+
+fn parse_date(value: &str) -> Result<NaiveDate, AppError> {
+    NaiveDate::parse_from_str(value.trim(), "%Y-%m-%d")
+        .map_err(|_| AppError::InvalidData(format!("无效的日期: {}，格式应为 YYYY-MM-DD", value)))
+}
+
+fn chat_timestamp(chat: &ChatSession) -> Option<i64> {
+    chat.created_at_ms.or(chat.updated_at_ms)
+}
+
+impl Granularity {
+    /// 日期所在时间段的第一天
+    fn bucket_start(self, date: NaiveDate) -> NaiveDate {
+        match self {
+            Granularity::Day => date,
+            Granularity::Week => date - Duration::days(date.weekday().num_days_from_monday() as i64),
+            Granularity::Month => date.with_day(1).unwrap_or(date),
+        }
+    }
+    
+    fn next(self, start: NaiveDate) -> NaiveDate {
+        match self {
+            Granularity::Day => start + Duration::days(1),
+            Granularity::Week => start + Duration::weeks(1),
+            Granularity::Month => start + Months::new(1),
+        }
+    }
+    
+    fn label(self, start: NaiveDate) -> String {
+        match self {
+            Granularity::Day => start.format("%Y-%m-%d").to_string(),
+            Granularity::Week => start.format("%G-W%V").to_string(),
+            Granularity::Month => start.format("%Y-%m").to_string(),
+        }
+    }
+}
+
+/// 是否属于指定项目：单项目工作区比较文件夹，多项目工作区看是否包含该项目
+fn matches_project(ws: &workspace_scan::WorkspaceScan, project: &str) -> bool {
+    ws.folder.as_deref().is_some_and(|f| workspace_uri::same_path(f, project))
+        || ws.projects.iter().any(|p| workspace_uri::same_path(p, project))
+}
+
+/// 按时间段汇总会话活动，可以只统计一个项目
+#[cfg_attr(feature = "gui", tauri::command)]
+pub fn get_activity_timeline(
+    granularity: Granularity,
+    range: Option<ActivityRange>,
+    project: Option<String>,
+) -> Result<ActivityTimeline, AppError> {
+    let range = range.unwrap_or_default();
+    let start = range.start.as_deref().map(parse_date).transpose()?;
+    let end = range.end.as_deref().map(parse_date).transpose()?;
+    if let (Some(start), Some(end)) = (start, end) {
+        if start > end {
+            return Err(AppError::InvalidData(format!("开始日期 {} 晚于结束日期 {}", start, end)));
+        }
+    }
+    
+    // 日期范围换算成时间戳范围，结束日期当天整天都包含在内
+    let zone = timezone::current();
+    let since = start.map(|d| zone.start_of_day_ms(d));
+    let until = end.map(|d| zone.start_of_day_ms(d + Duration::days(1)));
+    
+    let snapshot = workspace_scan::scan()?;
+    let mut seen = HashSet::new();
+    let mut buckets: BTreeMap<NaiveDate, ActivityBucket> = BTreeMap::new();
+    let mut undated_count = 0i64;
+    
+    for ws in &snapshot.workspaces {
+        if project.as_deref().is_some_and(|p| !matches_project(ws, p)) {
+            continue;
+        }
+        // 同一会话可能出现在多个工作区库中，只统计一次
+        for chat in ws.sessions.iter().filter(|c| seen.insert(c.id.clone())) {
+            let Some((ms, date)) = chat_timestamp(chat).and_then(|ms| Some((ms, zone.date(ms)?))) else {
+                undated_count += 1;
+                continue;
+            };
+            if since.is_some_and(|s| ms < s) || until.is_some_and(|u| ms >= u) {
+                continue;
+            }
+            
+            let bucket = buckets.entry(granularity.bucket_start(date)).or_default();
+            bucket.chat_count += 1;
+            if ChatMode::parse(&chat.mode) == ChatMode::Agent {
+                bucket.agent_count += 1;
+            } else {
+                bucket.chat_mode_count += 1;
+            }
+            bucket.lines_added += chat.lines_added;
+            bucket.lines_removed += chat.lines_removed;
+            bucket.files_changed += chat.files_changed;
+        }
+    }
+    
+    // 补齐没有活动的时间段，方便直接画图
+    let first = start.or_else(|| buckets.keys().next().copied());
+    let last = end.unwrap_or_else(|| zone.today());
+    let mut timeline = Vec::new();
+    if let Some(first) = first {
+        let mut cursor = granularity.bucket_start(first);
+        while cursor <= last {
+            let mut bucket = buckets.remove(&cursor).unwrap_or_default();
+            bucket.start = cursor.format("%Y-%m-%d").to_string();
+            bucket.label = granularity.label(cursor);
+            timeline.push(bucket);
+            cursor = granularity.next(cursor);
+        }
+    }
+    
+    Ok(ActivityTimeline {
+        granularity,
+        start: range.start,
+        end: range.end,
+        buckets: timeline,
+        undated_count,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    fn date(value: &str) -> NaiveDate {
+        parse_date(value).unwrap()
+    }
+    
+    #[test]
+    fn bucket_start_rounds_down_to_period() {
+        let cases = [
+            (Granularity::Day, "2024-03-07", "2024-03-07"),
+            // 2024-03-07 是周四，所在周从周一 03-04 开始
+            (Granularity::Week, "2024-03-07", "2024-03-04"),
+            (Granularity::Week, "2024-03-04", "2024-03-04"),
+            (Granularity::Week, "2024-03-10", "2024-03-04"),
+            // 跨月、跨年的周
+            (Granularity::Week, "2024-03-01", "2024-02-26"),
+            (Granularity::Week, "2025-01-01", "2024-12-30"),
+            (Granularity::Month, "2024-02-29", "2024-02-01"),
+            (Granularity::Month, "2024-12-01", "2024-12-01"),
+        ];
+        for (granularity, input, expected) in cases {
+            assert_eq!(granularity.bucket_start(date(input)), date(expected), "{:?} {}", granularity, input);
+        }
+    }
+    
+    #[test]
+    fn next_moves_one_period() {
+        let cases = [
+            (Granularity::Day, "2024-02-28", "2024-02-29"),
+            (Granularity::Day, "2024-12-31", "2025-01-01"),
+            (Granularity::Week, "2024-12-30", "2025-01-06"),
+            (Granularity::Month, "2024-01-01", "2024-02-01"),
+            (Granularity::Month, "2024-12-01", "2025-01-01"),
+        ];
+        for (granularity, input, expected) in cases {
+            assert_eq!(granularity.next(date(input)), date(expected), "{:?} {}", granularity, input);
+        }
+    }
+    
+    #[test]
+    fn label_formats_period() {
+        let cases = [
+            (Granularity::Day, "2024-03-05", "2024-03-05"),
+            (Granularity::Week, "2024-03-04", "2024-W10"),
+            // ISO 周按周四所在的年份计算
+            (Granularity::Week, "2024-12-30", "2025-W01"),
+            (Granularity::Week, "2021-01-04", "2021-W01"),
+            (Granularity::Week, "2020-12-28", "2020-W53"),
+            (Granularity::Month, "2024-03-01", "2024-03"),
+        ];
+        for (granularity, input, expected) in cases {
+            assert_eq!(granularity.label(date(input)), expected, "{:?} {}", granularity, input);
+        }
+    }
+}
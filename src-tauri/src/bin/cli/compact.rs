@@ -0,0 +1,52 @@
+//! 数据库压缩：compact
+
+use super::output::{print_json, print_table};
+use cursor_analysis_lib as app;
+
+pub fn run(global: bool, workspace: Option<String>, workspaces: bool, dry_run: bool, json: bool) -> Result<(), String> {
+    use app::compaction::CompactScope;
+    let scope = match (global, workspace, workspaces) {
+        (true, _, _) => CompactScope::Global,
+        (_, Some(workspace_id), _) => CompactScope::Workspace { workspace_id },
+        (_, _, true) => CompactScope::Workspaces,
+        _ => CompactScope::All,
+    };
+    let result = app::compaction::compact_databases(scope, dry_run)?;
+    if json {
+        return print_json(&result);
+    }
+    
+    let rows = result
+        .databases
+        .iter()
+        .map(|db| {
+            vec![
+                db.label.clone(),
+                app::format_size(db.size_before),
+                app::format_size(db.size_after),
+                db.bytes_saved_human.clone(),
+            ]
+        })
+        .collect();
+    let after = if dry_run { "压缩后（估算）" } else { "压缩后" };
+    print_table(&["数据库", "压缩前", after, "释放"], rows);
+    for f in &result.failures {
+        println!("失败 {}: {}", f.label, f.error);
+    }
+    if let Some(first) = result.skipped.first() {
+        println!("{}，未处理 {} 个数据库", first.error, result.skipped.len());
+        for f in &result.skipped {
+            println!("  {}", f.label);
+        }
+    }
+    println!();
+    println!(
+        "{} {} → {}，{} {}",
+        if dry_run { "预计" } else { "已压缩" },
+        app::format_size(result.size_before),
+        app::format_size(result.size_after),
+        if dry_run { "可释放" } else { "释放" },
+        result.bytes_saved_human
+    );
+    Ok(())
+}
@@ -0,0 +1,188 @@
+//! 本地文件历史：history
+
+use super::output::{exists_label, print_json, print_table};
+use clap::Subcommand;
+use cursor_analysis_lib as app;
+
+#[derive(Subcommand)]
+pub enum HistoryCommand {
+    /// 按项目汇总历史占用
+    Report {
+        /// 按文件列出
+        #[arg(long)]
+        files: bool,
+        /// 只列出源文件已不存在的历史
+        #[arg(long)]
+        missing: bool,
+        /// 最多显示的行数
+        #[arg(long)]
+        limit: Option<usize>,
+    },
+    /// 按策略清理历史版本，清理的文件移入回收区
+    Clean {
+        /// 删除早于 N 天的版本
+        #[arg(long, value_name = "N")]
+        older_than_days: Option<u32>,
+        /// 每个文件最多保留 K 个版本
+        #[arg(long, value_name = "K")]
+        keep_versions: Option<usize>,
+        /// 删除源文件已不存在的历史
+        #[arg(long)]
+        deleted_files: bool,
+        /// 删除所属项目已不存在的历史
+        #[arg(long)]
+        deleted_projects: bool,
+        /// 只列出将要删除的内容
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// 历史回收区
+    Trash {
+        #[command(subcommand)]
+        command: HistoryTrashCommand,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum HistoryTrashCommand {
+    /// 列出清理批次
+    List,
+    /// 恢复一个清理批次
+    Restore { batch_id: String },
+    /// 彻底删除清理批次，不指定时清空回收区
+    Purge { batch_id: Option<String> },
+}
+
+pub fn run(command: HistoryCommand, json: bool) -> Result<(), String> {
+    match command {
+        HistoryCommand::Report { files, missing, limit } => {
+            let mut report = app::history::get_history_report()?;
+            if missing {
+                report.files.retain(|f| f.exists == Some(false));
+                report.projects.retain(|p| p.missing_file_count > 0);
+            }
+            report.files.truncate(limit.unwrap_or(report.files.len()));
+            report.projects.truncate(limit.unwrap_or(report.projects.len()));
+            if json {
+                return print_json(&report);
+            }
+            
+            println!(
+                "共 {} 个文件、{} 个版本，占用 {}；源文件已删除的历史 {} 个，占用 {}",
+                report.file_count,
+                report.entry_count,
+                report.total_size_human,
+                report.missing_file_count,
+                app::format_size(report.missing_size)
+            );
+            println!();
+            if files {
+                let rows = report
+                    .files
+                    .iter()
+                    .map(|f| {
+                        vec![
+                            app::format_size(f.total_size),
+                            f.entry_count.to_string(),
+                            f.newest.clone().unwrap_or_default(),
+                            exists_label(f.exists).to_string(),
+                            f.path.clone(),
+                        ]
+                    })
+                    .collect();
+                print_table(&["大小", "版本", "最近修改", "状态", "文件"], rows);
+            } else {
+                let rows = report
+                    .projects
+                    .iter()
+                    .map(|p| {
+                        vec![
+                            p.name.clone(),
+                            p.total_size_human.clone(),
+                            p.file_count.to_string(),
+                            p.missing_file_count.to_string(),
+                            p.entry_count.to_string(),
+                            p.oldest.clone().unwrap_or_default(),
+                            exists_label(p.exists).to_string(),
+                            p.project_path.clone(),
+                        ]
+                    })
+                    .collect();
+                print_table(&["项目", "大小", "文件", "已删除", "版本", "最早", "状态", "路径"], rows);
+            }
+        }
+        HistoryCommand::Clean { older_than_days, keep_versions, deleted_files, deleted_projects, dry_run } => {
+            let result = app::history_cleanup::clean_history(app::history_cleanup::HistoryCleanupPolicy {
+                older_than_days,
+                keep_versions,
+                deleted_files,
+                deleted_projects,
+                dry_run,
+            })?;
+            if json {
+                return print_json(&result);
+            }
+            
+            let rows = result
+                .removals
+                .iter()
+                .map(|r| {
+                    vec![
+                        r.timestamp.clone().unwrap_or_default(),
+                        app::format_size(r.size),
+                        r.reason.label().to_string(),
+                        r.path.clone(),
+                    ]
+                })
+                .collect();
+            print_table(&["版本时间", "大小", "原因", "文件"], rows);
+            println!();
+            match &result.batch_id {
+                Some(id) => println!(
+                    "已清理 {} 个文件的 {} 个版本，释放 {}，回收批次 {}",
+                    result.file_count, result.removed_count, result.bytes_saved_human, id
+                ),
+                None => println!(
+                    "将清理 {} 个文件的 {} 个版本，可释放 {}",
+                    result.file_count, result.removed_count, result.bytes_saved_human
+                ),
+            }
+        }
+        HistoryCommand::Trash { command } => match command {
+            HistoryTrashCommand::List => {
+                let batches = app::history_cleanup::list_history_trash()?;
+                if json {
+                    return print_json(&batches);
+                }
+                let rows = batches
+                    .iter()
+                    .map(|b| {
+                        vec![
+                            b.id.clone(),
+                            b.created_at.clone(),
+                            b.file_count.to_string(),
+                            b.entry_count.to_string(),
+                            b.size_human.clone(),
+                        ]
+                    })
+                    .collect();
+                print_table(&["批次", "清理时间", "文件", "版本", "大小"], rows);
+            }
+            HistoryTrashCommand::Restore { batch_id } => {
+                let count = app::history_cleanup::restore_history_trash(batch_id)?;
+                if json {
+                    return print_json(&count);
+                }
+                println!("已恢复 {} 个文件的历史", count);
+            }
+            HistoryTrashCommand::Purge { batch_id } => {
+                let size = app::history_cleanup::purge_history_trash(batch_id)?;
+                if json {
+                    return print_json(&size);
+                }
+                println!("已彻底删除，释放 {}", app::format_size(size));
+            }
+        },
+    }
+    Ok(())
+}
@@ -0,0 +1,197 @@
+//! Cursor Analysis 命令行版本，与桌面应用共用同一套分析代码，不依赖 WebView
+
+mod compact;
+mod history;
+mod orphans;
+mod output;
+mod stats;
+mod storage;
+mod trash;
+mod usage;
+mod watch;
+
+use clap::{Parser, Subcommand};
+use cursor_analysis_lib as app;
+use history::HistoryCommand;
+use orphans::{OrphanCommand, OrphanDataCommand};
+use stats::{ChatSort, TimelineGranularity};
+use std::path::PathBuf;
+use std::process::ExitCode;
+use trash::{DeleteCommand, TrashCommand};
+use usage::UsageCommand;
+
+#[derive(Parser)]
+#[command(name = "cursor-analysis-cli", version, about = "Cursor IDE 使用分析工具（命令行版）")]
+struct Cli {
+    /// 以 JSON 格式输出
+    #[arg(long, global = true)]
+    json: bool,
+    
+    /// Cursor 的 User 目录，覆盖环境变量和设置文件（用于便携版或拷贝的配置目录）
+    #[arg(long, global = true, value_name = "PATH")]
+    data_root: Option<PathBuf>,
+    
+    /// 分析的编辑器：cursor、cursor-nightly、windsurf、vscode-insiders、vscodium
+    #[arg(long, global = true, value_name = "ID")]
+    editor: Option<String>,
+    
+    /// 显示时间使用的 IANA 时区（如 Asia/Shanghai、UTC），覆盖设置文件，local 表示系统时区
+    #[arg(long, global = true, value_name = "TZ")]
+    timezone: Option<String>,
+    
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// 存储占用
+    Storage,
+    /// 全局数据库统计
+    Database,
+    /// 按 key 前缀统计 cursorDiskKV 和 ItemTable
+    Keys {
+        /// 统计指定工作区的库，默认为全局库
+        #[arg(long, value_name = "ID")]
+        workspace: Option<String>,
+        /// 每个前缀显示最大的 N 个 key
+        #[arg(long, default_value_t = 0)]
+        top: usize,
+    },
+    /// 概览统计
+    Overview,
+    /// 项目列表
+    Projects {
+        /// 最多显示的项目数量
+        #[arg(long)]
+        limit: Option<usize>,
+    },
+    /// 会话列表，默认按占用空间排序
+    Chats {
+        /// 排序方式
+        #[arg(long, value_enum, default_value_t = ChatSort::Size)]
+        sort: ChatSort,
+        /// 最多显示的会话数量
+        #[arg(long, default_value_t = 20)]
+        limit: usize,
+    },
+    /// 工作区列表
+    Workspaces {
+        /// 最多显示的工作区数量
+        #[arg(long)]
+        limit: Option<usize>,
+    },
+    /// 完整分析结果（等同于桌面应用首页加载的数据）
+    Analysis,
+    /// 并排对比本机安装的各个编辑器
+    Editors,
+    /// 垃圾桶操作
+    Trash {
+        #[command(subcommand)]
+        command: TrashCommand,
+    },
+    /// 删除会话（删除的会话会进入垃圾桶）
+    Delete {
+        #[command(subcommand)]
+        command: DeleteCommand,
+    },
+    /// 本地文件历史（User/History）
+    History {
+        #[command(subcommand)]
+        command: HistoryCommand,
+    },
+    /// 孤立的 workspaceStorage 目录（项目已删除或 workspace.json 损坏）
+    Orphans {
+        #[command(subcommand)]
+        command: OrphanCommand,
+    },
+    /// 全局库中不属于任何会话的 composerData、bubble、checkpoint 数据
+    OrphanData {
+        #[command(subcommand)]
+        command: OrphanDataCommand,
+    },
+    /// 按天、周或月汇总会话活动
+    Timeline {
+        /// 时间段粒度
+        #[arg(long, value_enum, default_value_t = TimelineGranularity::Week)]
+        granularity: TimelineGranularity,
+        /// 开始日期 YYYY-MM-DD
+        #[arg(long)]
+        from: Option<String>,
+        /// 结束日期 YYYY-MM-DD，默认为今天
+        #[arg(long)]
+        to: Option<String>,
+        /// 只统计指定项目
+        #[arg(long, value_name = "PATH")]
+        project: Option<String>,
+    },
+    /// 长期统计（包括已删除会话的累计数据）
+    Usage {
+        #[command(subcommand)]
+        command: UsageCommand,
+    },
+    /// 持续监听数据库变化，输出新增和更新的会话（Ctrl+C 退出）
+    Watch,
+    /// 压缩 state.vscdb，释放删除会话后留下的空闲页（默认处理全局库和所有工作区库）
+    Compact {
+        /// 只压缩全局库
+        #[arg(long, conflicts_with_all = ["workspace", "workspaces"])]
+        global: bool,
+        /// 只压缩指定工作区的库
+        #[arg(long, value_name = "ID", conflicts_with = "workspaces")]
+        workspace: Option<String>,
+        /// 只压缩所有工作区库
+        #[arg(long)]
+        workspaces: bool,
+        /// 只做完整性检查并估算可释放的空间
+        #[arg(long)]
+        dry_run: bool,
+    },
+}
+
+fn run(cli: Cli) -> Result<(), String> {
+    if let Some(id) = &cli.editor {
+        app::editor::set_override(id)?;
+    }
+    if let Some(path) = cli.data_root {
+        app::data_root::set_override(path)?;
+    }
+    if let Some(name) = &cli.timezone {
+        app::timezone::set_override(name)?;
+    }
+    
+    match cli.command {
+        Command::Storage => storage::storage(cli.json),
+        Command::Database => storage::database(cli.json),
+        Command::Keys { workspace, top } => storage::keys(workspace, top, cli.json),
+        Command::Overview => stats::overview(cli.json),
+        Command::Editors => stats::editors(cli.json),
+        Command::Projects { limit } => stats::projects(limit, cli.json),
+        Command::Chats { sort, limit } => stats::chats(sort, limit, cli.json),
+        Command::Workspaces { limit } => stats::workspaces(limit, cli.json),
+        Command::Analysis => stats::analysis(),
+        Command::Trash { command } => trash::trash(command, cli.json),
+        Command::Delete { command } => trash::delete(command, cli.json),
+        Command::History { command } => history::run(command, cli.json),
+        Command::Orphans { command } => orphans::orphans(command, cli.json),
+        Command::OrphanData { command } => orphans::orphan_data(command, cli.json),
+        Command::Timeline { granularity, from, to, project } => {
+            stats::timeline(granularity, from, to, project, cli.json)
+        }
+        Command::Usage { command } => usage::run(command, cli.json),
+        Command::Watch => watch::run(cli.json),
+        Command::Compact { global, workspace, workspaces, dry_run } => {
+            compact::run(global, workspace, workspaces, dry_run, cli.json)
+        }
+    }
+}
+
+fn main() -> ExitCode {
+    match run(Cli::parse()) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("错误: {}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
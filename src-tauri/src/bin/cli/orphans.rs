@@ -0,0 +1,148 @@
+//! 孤立数据：orphans（workspaceStorage 目录）、orphan-data（全局库中的会话数据）
+
+use super::output::{print_json, print_table};
+use clap::Subcommand;
+use cursor_analysis_lib as app;
+
+#[derive(Subcommand)]
+pub enum OrphanCommand {
+    /// 列出孤立目录
+    List,
+    /// 移动到归档区
+    Archive {
+        /// 目录名，与 --all 二选一
+        ids: Vec<String>,
+        /// 处理所有孤立目录
+        #[arg(long, conflicts_with = "ids")]
+        all: bool,
+    },
+    /// 直接删除
+    Delete {
+        /// 目录名，与 --all 二选一
+        ids: Vec<String>,
+        /// 处理所有孤立目录
+        #[arg(long, conflicts_with = "ids")]
+        all: bool,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum OrphanDataCommand {
+    /// 列出孤立数据
+    List,
+    /// 移入垃圾桶
+    Purge {
+        /// 会话 ID，与 --all 二选一
+        ids: Vec<String>,
+        /// 处理全部孤立数据
+        #[arg(long, conflicts_with = "ids")]
+        all: bool,
+    },
+}
+
+/// 命令行指定的目录，--all 时取扫描结果
+fn orphan_ids(ids: Vec<String>, all: bool) -> Result<Vec<String>, String> {
+    if all {
+        return Ok(app::workspace_orphans::scan_orphaned_workspaces()?
+            .workspaces
+            .into_iter()
+            .map(|w| w.id)
+            .collect());
+    }
+    if ids.is_empty() {
+        return Err("请指定目录名或使用 --all".to_string());
+    }
+    Ok(ids)
+}
+
+pub fn orphans(command: OrphanCommand, json: bool) -> Result<(), String> {
+    let (ids, action) = match command {
+        OrphanCommand::List => {
+            let report = app::workspace_orphans::scan_orphaned_workspaces()?;
+            if json {
+                return print_json(&report);
+            }
+            println!("共 {} 个孤立目录，占用 {}", report.workspaces.len(), report.total_size_human);
+            println!();
+            let rows = report
+                .workspaces
+                .iter()
+                .map(|w| {
+                    vec![
+                        w.id.clone(),
+                        w.size_human.clone(),
+                        w.chat_count.to_string(),
+                        w.last_modified.clone().unwrap_or_default(),
+                        w.reason.label().to_string(),
+                        w.target.clone().unwrap_or_default(),
+                    ]
+                })
+                .collect();
+            print_table(&["目录", "大小", "会话", "最后修改", "原因", "路径"], rows);
+            return Ok(());
+        }
+        OrphanCommand::Archive { ids, all } => (orphan_ids(ids, all)?, app::workspace_orphans::OrphanAction::Archive),
+        OrphanCommand::Delete { ids, all } => (orphan_ids(ids, all)?, app::workspace_orphans::OrphanAction::Delete),
+    };
+    
+    let result = app::workspace_orphans::remove_orphaned_workspaces(ids, action)?;
+    if json {
+        return print_json(&result);
+    }
+    for skip in &result.skipped {
+        println!("跳过 {}: {}", skip.id, skip.error);
+    }
+    println!("已处理 {} 个目录，释放 {}", result.removed.len(), result.bytes_freed_human);
+    if let Some(dir) = &result.archive_dir {
+        println!("归档位置: {}", dir);
+    }
+    Ok(())
+}
+
+pub fn orphan_data(command: OrphanDataCommand, json: bool) -> Result<(), String> {
+    match command {
+        OrphanDataCommand::List => {
+            let report = app::kv_orphans::scan_orphaned_kv()?;
+            if json {
+                return print_json(&report);
+            }
+            println!(
+                "已登记 {} 个会话，孤立数据 {} 组，占用 {}",
+                report.known_composer_count,
+                report.composers.len(),
+                report.total_size_human
+            );
+            println!();
+            let rows = report
+                .composers
+                .iter()
+                .map(|c| {
+                    vec![
+                        c.composer_id.clone(),
+                        c.name.clone().unwrap_or_default(),
+                        c.size_human.clone(),
+                        c.bubble_count.to_string(),
+                        c.checkpoint_count.to_string(),
+                        c.agent_kv_count.to_string(),
+                        if c.has_composer_data { "是" } else { "否" }.to_string(),
+                    ]
+                })
+                .collect();
+            print_table(&["会话 ID", "名称", "大小", "bubble", "checkpoint", "agentKv", "composerData"], rows);
+        }
+        OrphanDataCommand::Purge { ids, all } => {
+            if ids.is_empty() && !all {
+                return Err("请指定会话 ID 或使用 --all".to_string());
+            }
+            let result = app::kv_orphans::purge_orphaned_kv(if all { None } else { Some(ids) })?;
+            if json {
+                return print_json(&result);
+            }
+            println!(
+                "已将 {} 组孤立数据（{} 条记录，{}）移入垃圾桶",
+                result.purged_count, result.kv_count, result.bytes_freed_human
+            );
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,64 @@
+//! 表格和 JSON 输出
+
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+pub fn print_json<T: Serialize>(value: &T) -> Result<(), String> {
+    let text = serde_json::to_string_pretty(value).map_err(|e| e.to_string())?;
+    println!("{}", text);
+    Ok(())
+}
+
+/// 按列宽对齐输出表格，第一行为表头
+pub fn print_table(header: &[&str], rows: Vec<Vec<String>>) {
+    let mut widths: Vec<usize> = header.iter().map(|h| display_width(h)).collect();
+    for row in &rows {
+        for (i, cell) in row.iter().enumerate() {
+            widths[i] = widths[i].max(display_width(cell));
+        }
+    }
+    
+    let format_row = |cells: Vec<String>| {
+        cells
+            .iter()
+            .enumerate()
+            .map(|(i, c)| format!("{}{}", c, " ".repeat(widths[i] - display_width(c))))
+            .collect::<Vec<_>>()
+            .join("  ")
+            .trim_end()
+            .to_string()
+    };
+    
+    println!("{}", format_row(header.iter().map(|h| h.to_string()).collect()));
+    println!("{}", widths.iter().map(|w| "-".repeat(*w)).collect::<Vec<_>>().join("  "));
+    for row in rows {
+        println!("{}", format_row(row));
+    }
+}
+
+/// 中文等全角字符按两列宽计算
+fn display_width(s: &str) -> usize {
+    s.chars().map(|c| if c.is_ascii() { 1 } else { 2 }).sum()
+}
+
+pub fn exists_label(exists: Option<bool>) -> &'static str {
+    match exists {
+        Some(true) => "存在",
+        Some(false) => "已删除",
+        None => "-",
+    }
+}
+
+pub fn print_pairs(pairs: Vec<(&str, String)>) {
+    let rows = pairs.into_iter().map(|(k, v)| vec![k.to_string(), v]).collect();
+    print_table(&["项目", "值"], rows);
+}
+
+/// 模式的原始值明细，如 `ask 3, chat 2`，空值显示为 `(空)`
+pub fn raw_modes(modes: &BTreeMap<String, i64>) -> String {
+    modes
+        .iter()
+        .map(|(mode, count)| format!("{} {}", if mode.is_empty() { "(空)" } else { mode }, count))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
@@ -0,0 +1,226 @@
+//! 会话统计：overview、editors、projects、chats、workspaces、analysis、timeline
+
+use super::output::{print_json, print_pairs, print_table, raw_modes};
+use clap::ValueEnum;
+use cursor_analysis_lib as app;
+
+#[derive(Clone, Copy, ValueEnum)]
+pub enum ChatSort {
+    /// 占用空间
+    Size,
+    /// 添加行数
+    Lines,
+    /// 更新时间
+    Updated,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+pub enum TimelineGranularity {
+    Day,
+    Week,
+    Month,
+}
+
+pub fn overview(json: bool) -> Result<(), String> {
+    let overview = app::get_overview()?;
+    if json {
+        return print_json(&overview);
+    }
+    print_pairs(vec![
+        ("项目数量", overview.total_projects.to_string()),
+        ("聊天会话", overview.total_chats.to_string()),
+        ("添加代码", overview.total_lines_added.to_string()),
+        ("删除代码", overview.total_lines_removed.to_string()),
+        ("净增代码", overview.net_lines.to_string()),
+        ("变更文件", overview.total_files_changed.to_string()),
+        ("Agent 模式", overview.agent_mode_count.to_string()),
+        ("Chat 模式", overview.chat_mode_count.to_string()),
+    ]);
+    if overview.locations.len() > 1 {
+        println!();
+        let rows = overview
+            .locations
+            .iter()
+            .map(|l| {
+                vec![
+                    l.label.clone(),
+                    l.project_count.to_string(),
+                    l.chat_count.to_string(),
+                    format!("+{}", l.lines_added),
+                    format!("-{}", l.lines_removed),
+                ]
+            })
+            .collect();
+        print_table(&["位置", "项目", "会话", "添加", "删除"], rows);
+    }
+    println!();
+    let mut modes: Vec<_> = overview.modes.values().collect();
+    modes.sort_by_key(|m| std::cmp::Reverse(m.chat_count));
+    let rows = modes
+        .into_iter()
+        .map(|m| {
+            vec![
+                m.label.clone(),
+                raw_modes(&m.raw_modes),
+                m.chat_count.to_string(),
+                format!("+{}", m.lines_added),
+                format!("-{}", m.lines_removed),
+                m.files_changed.to_string(),
+            ]
+        })
+        .collect();
+    print_table(&["模式", "原始值", "会话", "添加", "删除", "文件"], rows);
+    Ok(())
+}
+
+pub fn editors(json: bool) -> Result<(), String> {
+    let summaries = app::editor::get_editor_summaries();
+    if json {
+        return print_json(&summaries);
+    }
+    let rows = summaries
+        .iter()
+        .map(|s| {
+            let name = if s.active { format!("{} *", s.editor.name) } else { s.editor.name.to_string() };
+            let storage = s.storage.as_ref().map(|i| i.total_size_human.clone()).unwrap_or_else(|| "-".into());
+            let chats = s.overview.as_ref().map(|o| o.total_chats.to_string()).unwrap_or_else(|| "-".into());
+            let status = match (&s.error, s.installed) {
+                (Some(e), _) => e.clone(),
+                (None, true) => s.data_root.clone(),
+                (None, false) => "未安装".into(),
+            };
+            vec![s.editor.id.to_string(), name, storage, chats, status]
+        })
+        .collect();
+    print_table(&["ID", "编辑器", "存储", "会话", "数据目录"], rows);
+    Ok(())
+}
+
+pub fn projects(limit: Option<usize>, json: bool) -> Result<(), String> {
+    let mut projects = app::get_all_projects()?;
+    projects.truncate(limit.unwrap_or(projects.len()));
+    if json {
+        return print_json(&projects);
+    }
+    let rows = projects
+        .iter()
+        .map(|p| {
+            vec![
+                p.name.clone(),
+                p.chat_count.to_string(),
+                format!("+{}", p.lines_added),
+                format!("-{}", p.lines_removed),
+                p.files_changed.to_string(),
+                app::format_size(p.storage_size),
+                p.location.label(),
+                p.path.clone(),
+            ]
+        })
+        .collect();
+    print_table(&["项目", "会话", "添加", "删除", "文件", "占用", "位置", "路径"], rows);
+    Ok(())
+}
+
+pub fn chats(sort: ChatSort, limit: usize, json: bool) -> Result<(), String> {
+    let mut chats: Vec<(String, app::ChatSession)> = app::get_all_projects()?
+        .into_iter()
+        .flat_map(|p| {
+            let name = p.name;
+            p.chats.into_iter().map(move |c| (name.clone(), c))
+        })
+        .collect();
+    match sort {
+        ChatSort::Size => chats.sort_by_key(|(_, c)| std::cmp::Reverse(c.storage_size)),
+        ChatSort::Lines => chats.sort_by_key(|(_, c)| std::cmp::Reverse(c.lines_added)),
+        ChatSort::Updated => chats.sort_by_key(|(_, c)| std::cmp::Reverse(c.updated_at_ms)),
+    }
+    chats.truncate(limit);
+    if json {
+        let chats: Vec<&app::ChatSession> = chats.iter().map(|(_, c)| c).collect();
+        return print_json(&chats);
+    }
+    let rows = chats
+        .iter()
+        .map(|(project, c)| {
+            vec![
+                c.name.clone(),
+                project.clone(),
+                app::format_size(c.storage_size),
+                format!("+{}", c.lines_added),
+                c.updated_at.clone().unwrap_or_default(),
+                c.id.clone(),
+            ]
+        })
+        .collect();
+    print_table(&["会话", "项目", "占用", "添加", "更新时间", "ID"], rows);
+    Ok(())
+}
+
+pub fn workspaces(limit: Option<usize>, json: bool) -> Result<(), String> {
+    let mut workspaces = app::get_workspaces()?;
+    workspaces.truncate(limit.unwrap_or(workspaces.len()));
+    if json {
+        return print_json(&workspaces);
+    }
+    let rows = workspaces
+        .iter()
+        .map(|w| {
+            vec![
+                w.id.clone(),
+                w.chat_count.to_string(),
+                format!("+{}", w.lines_added),
+                format!("-{}", w.lines_removed),
+                w.projects.join(", "),
+            ]
+        })
+        .collect();
+    print_table(&["工作区", "会话", "添加", "删除", "项目"], rows);
+    Ok(())
+}
+
+pub fn analysis() -> Result<(), String> {
+    // 完整结果字段太多，只提供 JSON 输出
+    print_json(&app::get_full_analysis()?)
+}
+
+pub fn timeline(
+    granularity: TimelineGranularity,
+    from: Option<String>,
+    to: Option<String>,
+    project: Option<String>,
+    json: bool,
+) -> Result<(), String> {
+    use app::activity::{ActivityRange, Granularity};
+    let granularity = match granularity {
+        TimelineGranularity::Day => Granularity::Day,
+        TimelineGranularity::Week => Granularity::Week,
+        TimelineGranularity::Month => Granularity::Month,
+    };
+    let range = ActivityRange { start: from, end: to };
+    let timeline = app::activity::get_activity_timeline(granularity, Some(range), project)?;
+    if json {
+        return print_json(&timeline);
+    }
+    
+    let rows = timeline
+        .buckets
+        .iter()
+        .map(|b| {
+            vec![
+                b.label.clone(),
+                b.chat_count.to_string(),
+                b.agent_count.to_string(),
+                b.chat_mode_count.to_string(),
+                format!("+{}", b.lines_added),
+                format!("-{}", b.lines_removed),
+                b.files_changed.to_string(),
+            ]
+        })
+        .collect();
+    print_table(&["时间段", "会话", "Agent", "Chat", "新增", "删除", "文件"], rows);
+    if timeline.undated_count > 0 {
+        println!();
+        println!("{} 个会话没有时间信息，未计入", timeline.undated_count);
+    }
+    Ok(())
+}
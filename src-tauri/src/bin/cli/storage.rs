@@ -0,0 +1,65 @@
+//! 存储占用和数据库统计：storage、database、keys
+
+use super::output::{print_json, print_pairs, print_table};
+use cursor_analysis_lib as app;
+
+pub fn storage(json: bool) -> Result<(), String> {
+    let info = app::get_storage_info()?;
+    if json {
+        return print_json(&info);
+    }
+    print_pairs(vec![
+        ("总大小", info.total_size_human),
+        ("globalStorage", info.global_storage_size_human),
+        ("History", info.history_size_human),
+        ("workspaceStorage", info.workspace_storage_size_human),
+        ("state.vscdb", app::format_size(info.state_vscdb_size)),
+        ("state.vscdb.backup", app::format_size(info.state_vscdb_backup_size)),
+    ]);
+    Ok(())
+}
+
+pub fn database(json: bool) -> Result<(), String> {
+    let stats = app::get_database_stats()?;
+    if json {
+        return print_json(&stats);
+    }
+    print_table(
+        &["类型", "数量", "大小"],
+        vec![
+            vec!["ItemTable".into(), stats.item_table_count.to_string(), app::format_size(stats.item_table_size)],
+            vec!["cursorDiskKV".into(), stats.cursor_disk_kv_count.to_string(), app::format_size(stats.cursor_disk_kv_size)],
+            vec!["bubbleId".into(), stats.bubble_count.to_string(), app::format_size(stats.bubble_size)],
+            vec!["composerData".into(), stats.composer_count.to_string(), app::format_size(stats.composer_size)],
+            vec!["checkpointId".into(), stats.checkpoint_count.to_string(), app::format_size(stats.checkpoint_size)],
+            vec!["agentKv".into(), stats.agent_kv_count.to_string(), app::format_size(stats.agent_kv_size)],
+        ],
+    );
+    Ok(())
+}
+
+pub fn keys(workspace: Option<String>, top: usize, json: bool) -> Result<(), String> {
+    let breakdown = app::key_stats::get_key_breakdown(workspace, Some(top))?;
+    if json {
+        return print_json(&breakdown);
+    }
+    for table in &breakdown.tables {
+        println!("{}：{} 条，{}", table.table, table.count, table.total_size_human);
+        let mut rows = Vec::new();
+        for p in &table.prefixes {
+            rows.push(vec![
+                p.prefix.clone(),
+                p.count.to_string(),
+                p.total_size_human.clone(),
+                app::format_size(p.avg_size),
+                app::format_size(p.max_size),
+            ]);
+            for k in &p.top_keys {
+                rows.push(vec![format!("  {}", k.key), String::new(), app::format_size(k.size), String::new(), String::new()]);
+            }
+        }
+        print_table(&["前缀", "数量", "大小", "平均", "最大"], rows);
+        println!();
+    }
+    Ok(())
+}
@@ -0,0 +1,98 @@
+//! 垃圾桶和删除会话：trash、delete
+
+use super::output::{print_json, print_table};
+use clap::Subcommand;
+use cursor_analysis_lib as app;
+
+#[derive(Subcommand)]
+pub enum TrashCommand {
+    /// 列出垃圾桶中的会话
+    List,
+    /// 恢复垃圾桶中的会话
+    Restore {
+        /// 垃圾桶记录 ID
+        #[arg(required = true)]
+        ids: Vec<i64>,
+    },
+    /// 永久删除垃圾桶中的一条记录
+    Remove { id: i64 },
+    /// 清空垃圾桶
+    Clear,
+}
+
+#[derive(Subcommand)]
+pub enum DeleteCommand {
+    /// 删除项目中的指定会话
+    Chats {
+        project_path: String,
+        #[arg(required = true)]
+        chat_ids: Vec<String>,
+    },
+    /// 删除项目的全部会话
+    Project { project_path: String },
+    /// 删除工作区的全部会话
+    Workspace { workspace_id: String },
+}
+
+pub fn trash(command: TrashCommand, json: bool) -> Result<(), String> {
+    match command {
+        TrashCommand::List => {
+            let items = app::get_trash_items()?;
+            if json {
+                return print_json(&items);
+            }
+            let rows = items
+                .iter()
+                .map(|i| {
+                    vec![
+                        i.id.to_string(),
+                        i.chat_name.clone(),
+                        i.mode.clone(),
+                        i.deleted_at.clone(),
+                        i.kv_count.to_string(),
+                        i.project_path.clone(),
+                    ]
+                })
+                .collect();
+            print_table(&["ID", "会话", "模式", "删除时间", "数据行", "项目"], rows);
+        }
+        TrashCommand::Restore { ids } => {
+            let result = app::restore_trash_items(ids)?;
+            if json {
+                return print_json(&result);
+            }
+            println!("已恢复 {} 个会话", result.restored_count);
+            for f in &result.failures {
+                eprintln!("恢复失败 #{} {}: {}", f.trash_id, f.chat_name, f.error);
+            }
+        }
+        TrashCommand::Remove { id } => {
+            let removed = app::delete_trash_item(id)?;
+            if json {
+                return print_json(&removed);
+            }
+            println!("{}", if removed { "已删除" } else { "记录不存在" });
+        }
+        TrashCommand::Clear => {
+            let count = app::clear_trash()?;
+            if json {
+                return print_json(&count);
+            }
+            println!("已清空垃圾桶，共 {} 条记录", count);
+        }
+    }
+    Ok(())
+}
+
+pub fn delete(command: DeleteCommand, json: bool) -> Result<(), String> {
+    let count = match command {
+        DeleteCommand::Chats { project_path, chat_ids } => app::delete_chats_batch(project_path, chat_ids)?,
+        DeleteCommand::Project { project_path } => app::delete_project_chats(project_path)?,
+        DeleteCommand::Workspace { workspace_id } => app::delete_workspace_chats(workspace_id)?,
+    };
+    if json {
+        return print_json(&count);
+    }
+    println!("已删除 {} 个会话，可在垃圾桶中恢复", count);
+    Ok(())
+}
@@ -0,0 +1,109 @@
+//! 长期统计：usage
+
+use super::output::{print_json, print_pairs, print_table, raw_modes};
+use clap::Subcommand;
+use cursor_analysis_lib as app;
+
+#[derive(Subcommand)]
+pub enum UsageCommand {
+    /// 立即扫描并记录一次（桌面应用每次加载数据时自动记录）
+    Record,
+    /// 累计数据
+    Totals {
+        /// 最多显示的项目数量
+        #[arg(long, default_value_t = 10)]
+        limit: usize,
+    },
+    /// 每天的存储大小
+    Storage {
+        /// 只显示最近若干天
+        #[arg(long)]
+        days: Option<u32>,
+    },
+}
+
+pub fn run(command: UsageCommand, json: bool) -> Result<(), String> {
+    match command {
+        UsageCommand::Record => {
+            let result = app::usage_history::record_usage_snapshot()?;
+            if json {
+                return print_json(&result);
+            }
+            println!(
+                "已记录 {}：{} 个会话，新增 {} 个，已删除 {} 个",
+                result.day, result.chat_count, result.new_chat_count, result.deleted_chat_count
+            );
+        }
+        UsageCommand::Totals { limit } => {
+            let totals = app::usage_history::get_usage_totals()?;
+            if json {
+                return print_json(&totals);
+            }
+            let period = format!(
+                "{} ~ {}",
+                totals.first_recorded_at.as_deref().unwrap_or("-"),
+                totals.last_recorded_at.as_deref().unwrap_or("-")
+            );
+            print_pairs(vec![
+                ("记录时间", period),
+                ("会话", format!("{}（已删除 {}）", totals.chat_count, totals.deleted_chat_count)),
+                ("新增行数", format!("+{}（已删除会话 +{}）", totals.lines_added, totals.deleted_lines_added)),
+                ("删除行数", format!("-{}（已删除会话 -{}）", totals.lines_removed, totals.deleted_lines_removed)),
+                ("修改文件", totals.files_changed.to_string()),
+                ("平均上下文占用", format!("{:.1}%", totals.avg_context_usage)),
+            ]);
+            println!();
+            let rows = totals
+                .modes
+                .iter()
+                .map(|m| {
+                    vec![
+                        m.label.clone(),
+                        raw_modes(&m.raw_modes),
+                        m.chat_count.to_string(),
+                        format!("+{}", m.lines_added),
+                        format!("-{}", m.lines_removed),
+                    ]
+                })
+                .collect();
+            print_table(&["模式", "原始值", "会话", "新增", "删除"], rows);
+            println!();
+            let rows = totals
+                .projects
+                .iter()
+                .take(limit)
+                .map(|p| {
+                    vec![
+                        if p.project_path.is_empty() { "[多项目工作区]".to_string() } else { p.project_path.clone() },
+                        p.chat_count.to_string(),
+                        p.deleted_chat_count.to_string(),
+                        format!("+{}", p.lines_added),
+                        format!("-{}", p.lines_removed),
+                    ]
+                })
+                .collect();
+            print_table(&["项目", "会话", "已删除", "新增", "删除"], rows);
+        }
+        UsageCommand::Storage { days } => {
+            let trend = app::usage_history::get_storage_trend(days)?;
+            if json {
+                return print_json(&trend);
+            }
+            let rows = trend
+                .iter()
+                .map(|d| {
+                    vec![
+                        d.day.clone(),
+                        app::format_size(d.total_size),
+                        app::format_size(d.global_storage_size),
+                        app::format_size(d.workspace_storage_size),
+                        app::format_size(d.history_size),
+                        d.chat_count.to_string(),
+                    ]
+                })
+                .collect();
+            print_table(&["日期", "总计", "globalStorage", "workspaceStorage", "History", "会话"], rows);
+        }
+    }
+    Ok(())
+}
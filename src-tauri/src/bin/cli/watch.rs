@@ -0,0 +1,49 @@
+//! 持续监听：watch
+
+use cursor_analysis_lib as app;
+
+pub fn run(json: bool) -> Result<(), String> {
+    use app::watcher::WatchEvent;
+    // 输出不会失败，watch 只在监听线程断开时返回
+    let Ok(()) = app::watcher::watch::<std::convert::Infallible>(|event| {
+        if json {
+            // 每行一个事件，便于其他程序逐行读取
+            if let Ok(line) = serde_json::to_string(&event) {
+                println!("{}", line);
+            }
+            return Ok(());
+        }
+        match event {
+            WatchEvent::ChatAdded(e) => println!(
+                "新会话  {}  {}  +{} -{}",
+                e.workspace_id, e.chat.name, e.chat.lines_added, e.chat.lines_removed
+            ),
+            WatchEvent::ChatUpdated(e) => println!(
+                "更新    {}  {}  +{} -{}  {}",
+                e.workspace_id,
+                e.chat.name,
+                e.chat.lines_added,
+                e.chat.lines_removed,
+                app::format_size(e.chat.storage_size)
+            ),
+            WatchEvent::StorageChanged(c) => {
+                for chat in &c.removed_chats {
+                    println!("已移除  {}  {}", chat.workspace_id, chat.chat_id);
+                }
+                println!(
+                    "存储变化: {} 个工作区{}{}",
+                    c.changed_workspaces.len(),
+                    if c.removed_workspaces.is_empty() {
+                        String::new()
+                    } else {
+                        format!("，{} 个工作区已删除", c.removed_workspaces.len())
+                    },
+                    if c.global_changed { "，全局库" } else { "" }
+                );
+            }
+            WatchEvent::WatcherError(e) => eprintln!("监听出错: {}", e.message),
+        }
+        Ok(())
+    });
+    Ok(())
+}
@@ -0,0 +1,179 @@
+//! 会话模式：composerData 中的 `unifiedMode` 原样保留，同时归一化为已知的模式。
+//! 概览和项目统计按归一化后的模式汇总，原始值及其会话数作为明细保留，
+//! 新版本 Cursor 加入的模式归入 [`ChatMode::Other`]，从明细中仍能看到原始值。
+
+use crate::ChatSession;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// 归一化后的会话模式
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum ChatMode {
+    Agent,
+    /// 问答模式（界面上的 Ask），VS Code Chat 的会话也归入这里
+    Chat,
+    Edit,
+    Plan,
+    /// 后台运行的 Agent
+    Background,
+    /// 没有记录模式
+    Unknown,
+    /// 无法识别的模式，原始值见统计的 `raw_modes`
+    Other,
+}
+
+impl ChatMode {
+    pub fn parse(mode: &str) -> Self {
+        match mode.trim().to_ascii_lowercase().as_str() {
+            "agent" => ChatMode::Agent,
+            "chat" | "ask" => ChatMode::Chat,
+            "edit" => ChatMode::Edit,
+            "plan" => ChatMode::Plan,
+            "background" => ChatMode::Background,
+            "" | "unknown" => ChatMode::Unknown,
+            _ => ChatMode::Other,
+        }
+    }
+    
+    pub fn label(&self) -> &'static str {
+        match self {
+            ChatMode::Agent => "Agent",
+            ChatMode::Chat => "Ask",
+            ChatMode::Edit => "Edit",
+            ChatMode::Plan => "Plan",
+            ChatMode::Background => "Background",
+            ChatMode::Unknown => "未知",
+            ChatMode::Other => "其他",
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ModeStats {
+    pub kind: ChatMode,
+    pub label: String,
+    pub chat_count: i64,
+    pub lines_added: i64,
+    pub lines_removed: i64,
+    pub files_changed: i64,
+    /// 归入这一模式的 `unifiedMode` 原始值及各自的会话数
+    pub raw_modes: BTreeMap<String, i64>,
+}
+
+/// 按归一化后的模式汇总
+pub type ModeBreakdown = BTreeMap<ChatMode, ModeStats>;
+
+fn entry(breakdown: &mut ModeBreakdown, kind: ChatMode) -> &mut ModeStats {
+    breakdown.entry(kind).or_insert_with(|| ModeStats {
+        kind,
+        label: kind.label().to_string(),
+        chat_count: 0,
+        lines_added: 0,
+        lines_removed: 0,
+        files_changed: 0,
+        raw_modes: BTreeMap::new(),
+    })
+}
+
+pub(crate) fn breakdown<'a>(chats: impl IntoIterator<Item = &'a ChatSession>) -> ModeBreakdown {
+    let mut result = ModeBreakdown::new();
+    for chat in chats {
+        let stats = entry(&mut result, ChatMode::parse(&chat.mode));
+        *stats.raw_modes.entry(chat.mode.clone()).or_default() += 1;
+        stats.chat_count += 1;
+        stats.lines_added += chat.lines_added;
+        stats.lines_removed += chat.lines_removed;
+        stats.files_changed += chat.files_changed;
+    }
+    result
+}
+
+/// 把 `other` 的统计合并到 `into`
+pub(crate) fn merge(into: &mut ModeBreakdown, other: &ModeBreakdown) {
+    for (kind, stats) in other {
+        let target = entry(into, *kind);
+        target.chat_count += stats.chat_count;
+        target.lines_added += stats.lines_added;
+        target.lines_removed += stats.lines_removed;
+        target.files_changed += stats.files_changed;
+        for (mode, count) in &stats.raw_modes {
+            *target.raw_modes.entry(mode.clone()).or_default() += count;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    fn chat(mode: &str, lines_added: i64) -> ChatSession {
+        ChatSession {
+            id: String::new(),
+            name: String::new(),
+            mode: mode.to_string(),
+            created_at: None,
+            updated_at: None,
+            created_at_ms: None,
+            updated_at_ms: None,
+            lines_added,
+            lines_removed: 0,
+            files_changed: 1,
+            context_usage: 0.0,
+            branch: String::new(),
+            is_archived: false,
+            subtitle: String::new(),
+            storage_size: 0,
+        }
+    }
+    
+    #[test]
+    fn parse_normalizes_modes() {
+        let cases = [
+            ("agent", ChatMode::Agent),
+            (" Agent ", ChatMode::Agent),
+            ("chat", ChatMode::Chat),
+            ("ASK", ChatMode::Chat),
+            ("edit", ChatMode::Edit),
+            ("plan", ChatMode::Plan),
+            ("background", ChatMode::Background),
+            ("", ChatMode::Unknown),
+            ("unknown", ChatMode::Unknown),
+            ("debug", ChatMode::Other),
+        ];
+        for (mode, expected) in cases {
+            assert_eq!(ChatMode::parse(mode), expected, "{:?}", mode);
+        }
+    }
+    
+    #[test]
+    fn breakdown_groups_by_normalized_mode() {
+        let chats = [chat("agent", 10), chat("ask", 1), chat("chat", 2), chat("debug", 3), chat("agent", 5)];
+        let result = breakdown(&chats);
+        
+        // ask 和 chat 归一化后都是 Ask，合并为一项，原始值留在明细中
+        assert_eq!(result.keys().copied().collect::<Vec<_>>(), [ChatMode::Agent, ChatMode::Chat, ChatMode::Other]);
+        assert_eq!(result[&ChatMode::Agent].chat_count, 2);
+        assert_eq!(result[&ChatMode::Agent].lines_added, 15);
+        let ask = &result[&ChatMode::Chat];
+        assert_eq!(ask.label, "Ask");
+        assert_eq!(ask.chat_count, 2);
+        assert_eq!(ask.lines_added, 3);
+        assert_eq!(ask.raw_modes, BTreeMap::from([("ask".to_string(), 1), ("chat".to_string(), 1)]));
+        assert_eq!(result[&ChatMode::Other].label, "其他");
+        assert_eq!(result[&ChatMode::Other].raw_modes, BTreeMap::from([("debug".to_string(), 1)]));
+    }
+    
+    #[test]
+    fn merge_adds_counts() {
+        let mut total = breakdown(&[chat("agent", 10), chat("ask", 1)]);
+        merge(&mut total, &breakdown(&[chat("agent", 5), chat("plan", 1), chat("chat", 2)]));
+        
+        assert_eq!(total[&ChatMode::Agent].chat_count, 2);
+        assert_eq!(total[&ChatMode::Agent].lines_added, 15);
+        assert_eq!(total[&ChatMode::Agent].files_changed, 2);
+        assert_eq!(total[&ChatMode::Plan].chat_count, 1);
+        assert_eq!(total[&ChatMode::Chat].chat_count, 2);
+        assert_eq!(total[&ChatMode::Chat].raw_modes.len(), 2);
+    }
+}
@@ -0,0 +1,215 @@
+//! state.vscdb 压缩：删除会话后空出来的页面只会留在空闲列表里，文件大小不变，
+//! 需要 VACUUM 重建数据库才能把空间还给磁盘。
+//!
+//! VACUUM 本身是事务性的，中途失败不会损坏原库；执行前先做 `PRAGMA integrity_check`，
+//! 有问题的库不做处理，Cursor 运行时直接拒绝；处理途中 Cursor 启动或数据库被占用时停止，剩下的库记为跳过。
+
+use crate::{cursor_guard, AppError};
+use rusqlite::Connection;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// 压缩范围
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum CompactScope {
+    /// 全局库 globalStorage/state.vscdb
+    Global,
+    /// 单个工作区库
+    Workspace { workspace_id: String },
+    /// 所有工作区库
+    Workspaces,
+    /// 全局库和所有工作区库
+    All,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct DbCompaction {
+    pub db_path: String,
+    /// global 或工作区 ID
+    pub label: String,
+    /// 压缩前大小（包括 WAL 文件）
+    pub size_before: u64,
+    pub size_after: u64,
+    /// 空闲页占用的空间，即预计可以释放的大小
+    pub reclaimable: u64,
+    pub bytes_saved: u64,
+    pub bytes_saved_human: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct CompactionFailure {
+    pub db_path: String,
+    pub label: String,
+    pub error: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct CompactionResult {
+    pub dry_run: bool,
+    pub databases: Vec<DbCompaction>,
+    /// 完整性检查不通过或压缩失败的库
+    pub failures: Vec<CompactionFailure>,
+    /// Cursor 中途启动或数据库被占用后没有处理的库，error 为停止的原因
+    pub skipped: Vec<CompactionFailure>,
+    pub size_before: u64,
+    pub size_after: u64,
+    pub bytes_saved: u64,
+    pub bytes_saved_human: String,
+}
+
+/// 数据库文件加上 WAL 文件的大小
+fn db_size(db_path: &Path) -> u64 {
+    let mut wal = db_path.as_os_str().to_owned();
+    wal.push("-wal");
+    [db_path.to_path_buf(), PathBuf::from(wal)]
+        .iter()
+        .map(|p| fs::metadata(p).map(|m| m.len()).unwrap_or(0))
+        .sum()
+}
+
+fn collect_databases(scope: &CompactScope) -> Result<Vec<(PathBuf, String)>, AppError> {
+    let mut databases = Vec::new();
+    
+    if matches!(scope, CompactScope::Global | CompactScope::All) {
+        let global_db = crate::get_global_db_path()?;
+        if global_db.exists() {
+            databases.push((global_db, "global".to_string()));
+        }
+    }
+    
+    let workspace_storage = crate::get_cursor_user_path()?.join("workspaceStorage");
+    match scope {
+        CompactScope::Workspace { workspace_id } => {
+            crate::validate_workspace_id(workspace_id)?;
+            let db_path = workspace_storage.join(workspace_id).join("state.vscdb");
+            if !db_path.exists() {
+                return Err(AppError::NotFound(format!("未找到工作区 {} 的数据库", workspace_id)));
+            }
+            databases.push((db_path, workspace_id.clone()));
+        }
+        CompactScope::Workspaces | CompactScope::All => {
+            if let Ok(entries) = fs::read_dir(&workspace_storage) {
+                let mut found: Vec<(PathBuf, String)> = entries
+                    .filter_map(|e| e.ok())
+                    .map(|e| e.path().join("state.vscdb"))
+                    .filter(|p| p.exists())
+                    .map(|p| {
+                        let label = crate::workspace_id_from_db_path(&p);
+                        (p, label)
+                    })
+                    .collect();
+                found.sort_by(|a, b| a.1.cmp(&b.1));
+                databases.extend(found);
+            }
+        }
+        CompactScope::Global => {}
+    }
+    
+    Ok(databases)
+}
+
+/// `PRAGMA integrity_check` 只返回一行 ok 时数据库完好
+fn check_integrity(conn: &Connection) -> Result<(), AppError> {
+    let mut stmt = conn.prepare("PRAGMA integrity_check")?;
+    let problems: Vec<String> = stmt
+        .query_map([], |row| row.get::<_, String>(0))?
+        .filter_map(|r| r.ok())
+        .filter(|r| r != "ok")
+        .collect();
+    if problems.is_empty() {
+        Ok(())
+    } else {
+        Err(AppError::InvalidData(format!("完整性检查未通过: {}", problems.join("; "))))
+    }
+}
+
+fn compact_database(db_path: &Path, label: String, dry_run: bool) -> Result<DbCompaction, AppError> {
+    let size_before = db_size(db_path);
+    let conn = Connection::open(db_path)?;
+    check_integrity(&conn)?;
+    
+    let page_size: i64 = conn.query_row("PRAGMA page_size", [], |row| row.get(0))?;
+    let freelist: i64 = conn.query_row("PRAGMA freelist_count", [], |row| row.get(0))?;
+    let reclaimable = (page_size * freelist) as u64;
+    
+    if !dry_run {
+        if cursor_guard::is_db_locked(db_path) {
+            return Err(AppError::CursorRunning(format!("数据库 {} 正被其他进程占用", db_path.display())));
+        }
+        // 先把 WAL 中的内容合并回主文件，再重建
+        conn.query_row("PRAGMA wal_checkpoint(TRUNCATE)", [], |_| Ok(()))?;
+        conn.execute_batch("VACUUM")?;
+    }
+    drop(conn);
+    
+    let size_after = if dry_run {
+        size_before.saturating_sub(reclaimable)
+    } else {
+        db_size(db_path)
+    };
+    let bytes_saved = size_before.saturating_sub(size_after);
+    
+    Ok(DbCompaction {
+        db_path: db_path.to_string_lossy().to_string(),
+        label,
+        size_before,
+        size_after,
+        reclaimable,
+        bytes_saved,
+        bytes_saved_human: crate::format_size(bytes_saved),
+    })
+}
+
+/// 压缩数据库并返回前后大小；dry_run 时只做完整性检查并按空闲页估算
+#[cfg_attr(feature = "gui", tauri::command)]
+pub fn compact_databases(scope: CompactScope, dry_run: bool) -> Result<CompactionResult, AppError> {
+    if !dry_run {
+        cursor_guard::ensure_not_running()?;
+    }
+    
+    let mut result = CompactionResult {
+        dry_run,
+        databases: Vec::new(),
+        failures: Vec::new(),
+        skipped: Vec::new(),
+        size_before: 0,
+        size_after: 0,
+        bytes_saved: 0,
+        bytes_saved_human: String::new(),
+    };
+    
+    let databases = collect_databases(&scope)?;
+    for (index, (db_path, label)) in databases.iter().enumerate() {
+        match compact_database(db_path, label.clone(), dry_run) {
+            Ok(db) => {
+                result.size_before += db.size_before;
+                result.size_after += db.size_after;
+                result.bytes_saved += db.bytes_saved;
+                result.databases.push(db);
+            }
+            // Cursor 在压缩途中启动时停止处理，已完成的结果照常返回，剩下的库记为跳过
+            Err(e @ AppError::CursorRunning(_)) => {
+                let reason = e.to_string();
+                result.skipped = databases[index..]
+                    .iter()
+                    .map(|(db_path, label)| CompactionFailure {
+                        db_path: db_path.to_string_lossy().to_string(),
+                        label: label.clone(),
+                        error: reason.clone(),
+                    })
+                    .collect();
+                break;
+            }
+            Err(e) => result.failures.push(CompactionFailure {
+                db_path: db_path.to_string_lossy().to_string(),
+                label: label.clone(),
+                error: e.to_string(),
+            }),
+        }
+    }
+    
+    result.bytes_saved_human = crate::format_size(result.bytes_saved);
+    Ok(result)
+}
@@ -0,0 +1,130 @@
+//! Cursor 运行状态检测：修改 state.vscdb 之前确认 Cursor 没有占用数据库
+
+use crate::AppError;
+use rusqlite::{Connection, OpenFlags};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use sysinfo::{ProcessRefreshKind, ProcessesToUpdate, System};
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct CursorStatus {
+    pub running: bool,
+    pub pids: Vec<u32>,
+    pub db_locked: bool,
+    pub wal_active: bool,
+}
+
+/// 查找正在运行的 Cursor 进程（按当前编辑器配置的进程名匹配，排除本工具自身）
+pub fn find_cursor_processes() -> Vec<u32> {
+    let profile = crate::editor::active();
+    let mut sys = System::new();
+    sys.refresh_processes_specifics(ProcessesToUpdate::All, true, ProcessRefreshKind::new());
+    
+    let current = sysinfo::get_current_pid().ok();
+    let mut pids: Vec<u32> = sys
+        .processes()
+        .iter()
+        .filter(|(pid, _)| Some(**pid) != current)
+        .filter(|(_, p)| profile.matches_process(&p.name().to_string_lossy()))
+        .map(|(pid, _)| pid.as_u32())
+        .collect();
+    pids.sort_unstable();
+    pids
+}
+
+/// 尝试获取写锁判断数据库是否被其他连接占用
+pub fn is_db_locked(db_path: &Path) -> bool {
+    let Ok(conn) = Connection::open_with_flags(db_path, OpenFlags::SQLITE_OPEN_READ_WRITE) else {
+        return false;
+    };
+    if conn.busy_timeout(Duration::ZERO).is_err() {
+        return false;
+    }
+    match conn.execute_batch("BEGIN IMMEDIATE") {
+        Ok(()) => {
+            let _ = conn.execute_batch("ROLLBACK");
+            false
+        }
+        Err(rusqlite::Error::SqliteFailure(e, _)) => matches!(
+            e.code,
+            rusqlite::ErrorCode::DatabaseBusy | rusqlite::ErrorCode::DatabaseLocked
+        ),
+        Err(_) => false,
+    }
+}
+
+/// WAL 文件非空说明还有连接未正常关闭
+pub fn is_wal_active(db_path: &Path) -> bool {
+    let mut wal = db_path.as_os_str().to_owned();
+    wal.push("-wal");
+    std::fs::metadata(PathBuf::from(wal)).map(|m| m.len() > 0).unwrap_or(false)
+}
+
+pub fn cursor_status(db_path: &Path) -> CursorStatus {
+    let pids = find_cursor_processes();
+    CursorStatus {
+        running: !pids.is_empty(),
+        pids,
+        db_locked: db_path.exists() && is_db_locked(db_path),
+        wal_active: is_wal_active(db_path),
+    }
+}
+
+/// Cursor 正在运行时拒绝修改（History 等不在数据库中的数据）
+pub fn ensure_not_running() -> Result<(), AppError> {
+    let pids = find_cursor_processes();
+    if !pids.is_empty() {
+        let name = crate::editor::active().name;
+        let pids: Vec<String> = pids.iter().map(|p| p.to_string()).collect();
+        return Err(AppError::CursorRunning(format!(
+            "{} 正在运行 (PID: {})，请先退出 {} 再修改数据",
+            name,
+            pids.join(", "),
+            name
+        )));
+    }
+    Ok(())
+}
+
+/// 把残留的 WAL 合并回数据库并截断，返回是否合并成功
+fn checkpoint_wal(db_path: &Path) -> Result<bool, AppError> {
+    let conn = Connection::open_with_flags(db_path, OpenFlags::SQLITE_OPEN_READ_WRITE)?;
+    conn.busy_timeout(Duration::ZERO)?;
+    let busy: i64 = conn.query_row("PRAGMA wal_checkpoint(TRUNCATE)", [], |row| row.get(0))?;
+    drop(conn);
+    Ok(busy == 0 && !is_wal_active(db_path))
+}
+
+/// 写入前检查：Cursor 正在运行或数据库被占用时拒绝修改。
+/// Cursor 未运行但 WAL 非空说明上次没有正常退出，先合并 WAL，保证快照和修改基于完整的数据
+pub fn ensure_writable(db_path: &Path) -> Result<(), AppError> {
+    ensure_not_running()?;
+    
+    if !db_path.exists() {
+        return Ok(());
+    }
+    
+    if is_db_locked(db_path) {
+        return Err(AppError::CursorRunning(format!(
+            "数据库 {} 正被其他进程占用",
+            db_path.display()
+        )));
+    }
+    
+    if is_wal_active(db_path) && !checkpoint_wal(db_path)? {
+        let name = crate::editor::active().name;
+        return Err(AppError::CursorRunning(format!(
+            "数据库 {} 有未合并的 WAL 日志，请先打开并正常退出 {} 后再修改数据",
+            db_path.display(),
+            name
+        )));
+    }
+    
+    Ok(())
+}
+
+#[cfg_attr(feature = "gui", tauri::command)]
+pub fn get_cursor_status() -> Result<CursorStatus, AppError> {
+    Ok(cursor_status(&crate::get_global_db_path()?))
+}
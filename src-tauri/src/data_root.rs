@@ -0,0 +1,116 @@
+//! Cursor 用户数据目录（User 目录）的定位
+//!
+//! 优先级：命令行 `--data-root` > 环境变量 `CURSOR_ANALYSIS_DATA_ROOT` > 设置文件 > 当前编辑器的系统默认路径。
+//! 自定义目录可以是便携版安装、从其他机器拷贝的配置目录或测试数据。
+
+use crate::{editor, settings, AppError};
+use serde::{Deserialize, Serialize};
+use std::cell::RefCell;
+use std::path::{Path, PathBuf};
+use std::sync::RwLock;
+
+/// 指定数据目录的环境变量
+pub const DATA_ROOT_ENV: &str = "CURSOR_ANALYSIS_DATA_ROOT";
+
+/// 命令行指定的数据目录，进程内有效
+static OVERRIDE: RwLock<Option<PathBuf>> = RwLock::new(None);
+
+thread_local! {
+    /// 临时切换的数据目录，只影响当前线程
+    static SCOPED: RefCell<Option<PathBuf>> = const { RefCell::new(None) };
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum DataRootSource {
+    CommandLine,
+    Environment,
+    Settings,
+    Default,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct DataRootInfo {
+    pub path: String,
+    pub source: DataRootSource,
+    pub exists: bool,
+}
+
+/// 设置进程内的数据目录（命令行参数使用）
+pub fn set_override(path: PathBuf) -> Result<(), AppError> {
+    validate(&path)?;
+    *OVERRIDE.write().unwrap_or_else(|e| e.into_inner()) = Some(path);
+    Ok(())
+}
+
+/// 检查目录是否像一个 Cursor User 目录
+pub fn validate(path: &Path) -> Result<(), AppError> {
+    if !path.is_dir() {
+        return Err(AppError::NotFound(format!("数据目录 {} 不存在", path.display())));
+    }
+    if !path.join("globalStorage").is_dir() && !path.join("workspaceStorage").is_dir() {
+        return Err(AppError::InvalidData(format!(
+            "{} 不是编辑器的 User 目录（缺少 globalStorage 和 workspaceStorage）",
+            path.display()
+        )));
+    }
+    Ok(())
+}
+
+/// 在当前线程临时使用指定的数据目录执行 `f`
+pub fn with_root<T>(root: &Path, f: impl FnOnce() -> T) -> T {
+    let previous = SCOPED.with(|s| s.replace(Some(root.to_path_buf())));
+    let result = f();
+    SCOPED.with(|s| *s.borrow_mut() = previous);
+    result
+}
+
+/// 按优先级解析数据目录，自定义目录必须通过校验
+pub fn resolve() -> Result<(PathBuf, DataRootSource), AppError> {
+    if let Some(path) = SCOPED.with(|s| s.borrow().clone()) {
+        return Ok((path, DataRootSource::Default));
+    }
+    
+    if let Some(path) = OVERRIDE.read().unwrap_or_else(|e| e.into_inner()).clone() {
+        return Ok((path, DataRootSource::CommandLine));
+    }
+    
+    if let Some(path) = std::env::var_os(DATA_ROOT_ENV).filter(|v| !v.is_empty()) {
+        let path = PathBuf::from(path);
+        validate(&path)?;
+        return Ok((path, DataRootSource::Environment));
+    }
+    
+    if let Some(path) = &settings::current().data_root {
+        let path = PathBuf::from(path);
+        validate(&path)?;
+        return Ok((path, DataRootSource::Settings));
+    }
+    
+    Ok((editor::active().default_user_path()?, DataRootSource::Default))
+}
+
+#[cfg_attr(feature = "gui", tauri::command)]
+pub fn get_data_root() -> Result<DataRootInfo, AppError> {
+    let (path, source) = resolve()?;
+    Ok(DataRootInfo {
+        path: path.to_string_lossy().to_string(),
+        exists: path.is_dir(),
+        source,
+    })
+}
+
+/// 保存自定义数据目录到设置文件，传入 None 恢复系统默认路径
+#[cfg_attr(feature = "gui", tauri::command)]
+pub fn set_data_root(path: Option<String>) -> Result<DataRootInfo, AppError> {
+    let path = path.map(|p| p.trim().to_string()).filter(|p| !p.is_empty());
+    if let Some(p) = &path {
+        validate(Path::new(p))?;
+    }
+    
+    let mut current = settings::load();
+    current.data_root = path;
+    settings::save(&current)?;
+    
+    get_data_root()
+}
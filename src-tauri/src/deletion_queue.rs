@@ -0,0 +1,155 @@
+//! 删除队列：Cursor 运行时先记录删除请求，等 Cursor 退出后再统一执行
+
+use crate::cursor_guard::find_cursor_processes;
+use crate::AppError;
+use chrono::Utc;
+use rusqlite::Connection;
+use serde::{Deserialize, Serialize};
+use std::thread;
+use std::time::Duration;
+
+/// 后台检查 Cursor 是否退出的间隔
+const WORKER_INTERVAL: Duration = Duration::from_secs(15);
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum DeletionRequest {
+    Chats { project_path: String, chat_ids: Vec<String> },
+    Project { project_path: String },
+    Workspace { workspace_id: String },
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PendingDeletion {
+    pub id: i64,
+    pub request: DeletionRequest,
+    pub queued_at: String,
+    pub last_error: Option<String>,
+}
+
+fn init_queue_db() -> Result<Connection, AppError> {
+    let conn = Connection::open(crate::get_trash_db_path()?)?;
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS pending_deletions (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            request TEXT NOT NULL,
+            queued_at TEXT NOT NULL,
+            last_error TEXT
+        )",
+        [],
+    )?;
+    Ok(conn)
+}
+
+fn load_pending(conn: &Connection) -> Result<Vec<PendingDeletion>, AppError> {
+    let mut stmt = conn.prepare("SELECT id, request, queued_at, last_error FROM pending_deletions ORDER BY id")?;
+    let rows = stmt
+        .query_map([], |row| {
+            Ok((
+                row.get::<_, i64>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, String>(2)?,
+                row.get::<_, Option<String>>(3)?,
+            ))
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+    
+    rows.into_iter()
+        .map(|(id, request, queued_at, last_error)| {
+            Ok(PendingDeletion {
+                id,
+                request: serde_json::from_str(&request)?,
+                queued_at,
+                last_error,
+            })
+        })
+        .collect()
+}
+
+fn execute_request(request: &DeletionRequest) -> Result<i64, AppError> {
+    match request {
+        DeletionRequest::Chats { project_path, chat_ids } => {
+            crate::delete_chats_batch(project_path.clone(), chat_ids.clone())
+        }
+        DeletionRequest::Project { project_path } => crate::delete_project_chats(project_path.clone()),
+        DeletionRequest::Workspace { workspace_id } => crate::delete_workspace_chats(workspace_id.clone()),
+    }
+}
+
+/// 依次执行队列中的删除请求，成功的移出队列，失败的记录错误留待手动处理
+fn apply_pending() -> Result<i64, AppError> {
+    let conn = init_queue_db()?;
+    let mut applied = 0i64;
+    
+    for pending in load_pending(&conn)? {
+        match execute_request(&pending.request) {
+            Ok(_) => {
+                conn.execute("DELETE FROM pending_deletions WHERE id = ?", [pending.id])?;
+                applied += 1;
+            }
+            // Cursor 又启动了，剩下的等下一轮
+            Err(AppError::CursorRunning(_)) => break,
+            Err(e) => {
+                conn.execute(
+                    "UPDATE pending_deletions SET last_error = ?1 WHERE id = ?2",
+                    rusqlite::params![e.to_string(), pending.id],
+                )?;
+            }
+        }
+    }
+    
+    Ok(applied)
+}
+
+fn has_retryable_pending() -> bool {
+    init_queue_db()
+        .and_then(|conn| {
+            conn.query_row("SELECT COUNT(*) FROM pending_deletions WHERE last_error IS NULL", [], |row| {
+                row.get::<_, i64>(0)
+            })
+            .map_err(AppError::from)
+        })
+        .map(|count| count > 0)
+        .unwrap_or(false)
+}
+
+/// 启动后台线程，Cursor 退出后自动执行队列中的删除
+pub fn spawn_worker() {
+    thread::spawn(|| loop {
+        thread::sleep(WORKER_INTERVAL);
+        if has_retryable_pending() && find_cursor_processes().is_empty() {
+            let _ = apply_pending();
+        }
+    });
+}
+
+#[cfg_attr(feature = "gui", tauri::command)]
+pub fn queue_deletion(request: DeletionRequest) -> Result<i64, AppError> {
+    if let DeletionRequest::Workspace { workspace_id } = &request {
+        crate::validate_workspace_id(workspace_id)?;
+    }
+    let conn = init_queue_db()?;
+    let queued_at = Utc::now().format("%Y-%m-%d %H:%M:%S").to_string();
+    conn.execute(
+        "INSERT INTO pending_deletions (request, queued_at) VALUES (?1, ?2)",
+        [serde_json::to_string(&request)?, queued_at],
+    )?;
+    Ok(conn.last_insert_rowid())
+}
+
+#[cfg_attr(feature = "gui", tauri::command)]
+pub fn get_pending_deletions() -> Result<Vec<PendingDeletion>, AppError> {
+    load_pending(&init_queue_db()?)
+}
+
+#[cfg_attr(feature = "gui", tauri::command)]
+pub fn cancel_pending_deletion(id: i64) -> Result<bool, AppError> {
+    let conn = init_queue_db()?;
+    let affected = conn.execute("DELETE FROM pending_deletions WHERE id = ?", [id])?;
+    Ok(affected > 0)
+}
+
+#[cfg_attr(feature = "gui", tauri::command)]
+pub fn apply_pending_deletions() -> Result<i64, AppError> {
+    apply_pending()
+}
@@ -0,0 +1,237 @@
+//! 编辑器配置：VS Code 系列编辑器共用 User/globalStorage、workspaceStorage/*/state.vscdb 的目录结构，
+//! 每个配置描述产品的数据目录、进程名以及保存 AI 会话列表的 ItemTable 键
+
+use crate::{data_root, settings, AppError, OverviewStats, StorageInfo};
+use serde::Serialize;
+use std::cell::Cell;
+use std::path::{Path, PathBuf};
+use std::sync::RwLock;
+
+/// 指定编辑器的环境变量
+pub const EDITOR_ENV: &str = "CURSOR_ANALYSIS_EDITOR";
+
+/// Cursor 的会话列表键，值为 `{ allComposers: [...] }`
+pub const COMPOSER_DATA_KEY: &str = "composer.composerData";
+
+/// VS Code Chat 的会话列表键，值为会话数组
+pub const INTERACTIVE_SESSIONS_KEY: &str = "interactive.sessions";
+
+#[derive(Debug, Serialize, Clone)]
+pub struct EditorProfile {
+    pub id: &'static str,
+    pub name: &'static str,
+    /// 系统应用数据目录下的文件夹名
+    pub app_dir: &'static str,
+    /// 进程名（小写、不含 .exe），对应的 Helper 进程也会匹配
+    pub process_names: &'static [&'static str],
+    /// 保存会话列表的 ItemTable 键，按顺序读取
+    pub chat_keys: &'static [&'static str],
+}
+
+pub static PROFILES: &[EditorProfile] = &[
+    EditorProfile {
+        id: "cursor",
+        name: "Cursor",
+        app_dir: "Cursor",
+        process_names: &["cursor"],
+        chat_keys: &[COMPOSER_DATA_KEY],
+    },
+    EditorProfile {
+        id: "cursor-nightly",
+        name: "Cursor Nightly",
+        app_dir: "Cursor Nightly",
+        process_names: &["cursor nightly"],
+        chat_keys: &[COMPOSER_DATA_KEY],
+    },
+    EditorProfile {
+        id: "windsurf",
+        name: "Windsurf",
+        app_dir: "Windsurf",
+        process_names: &["windsurf"],
+        // Cascade 会话不保存在 state.vscdb 中，只做存储分析
+        chat_keys: &[],
+    },
+    EditorProfile {
+        id: "vscode-insiders",
+        name: "VS Code Insiders",
+        app_dir: "Code - Insiders",
+        process_names: &["code - insiders", "code-insiders"],
+        chat_keys: &[INTERACTIVE_SESSIONS_KEY],
+    },
+    EditorProfile {
+        id: "vscodium",
+        name: "VSCodium",
+        app_dir: "VSCodium",
+        process_names: &["vscodium", "codium"],
+        chat_keys: &[INTERACTIVE_SESSIONS_KEY],
+    },
+];
+
+/// 命令行指定的编辑器，进程内有效
+static OVERRIDE: RwLock<Option<&'static EditorProfile>> = RwLock::new(None);
+
+thread_local! {
+    /// 并排对比时临时切换的编辑器，只影响当前线程
+    static SCOPED: Cell<Option<&'static EditorProfile>> = const { Cell::new(None) };
+}
+
+impl EditorProfile {
+    /// 系统默认的 User 目录
+    pub fn default_user_path(&self) -> Result<PathBuf, AppError> {
+        #[cfg(target_os = "macos")]
+        {
+            let home = dirs::home_dir().ok_or_else(|| AppError::NotFound("找不到用户主目录".to_string()))?;
+            Ok(home.join("Library/Application Support").join(self.app_dir).join("User"))
+        }
+        #[cfg(target_os = "windows")]
+        {
+            let appdata = std::env::var("APPDATA")
+                .map_err(|_| AppError::NotFound("找不到 APPDATA 环境变量".to_string()))?;
+            Ok(PathBuf::from(appdata).join(self.app_dir).join("User"))
+        }
+        #[cfg(target_os = "linux")]
+        {
+            let home = dirs::home_dir().ok_or_else(|| AppError::NotFound("找不到用户主目录".to_string()))?;
+            Ok(home.join(".config").join(self.app_dir).join("User"))
+        }
+    }
+    
+    pub fn matches_process(&self, name: &str) -> bool {
+        let name = name.to_lowercase();
+        let name = name.strip_suffix(".exe").unwrap_or(&name);
+        self.process_names
+            .iter()
+            .any(|p| name == *p || name.starts_with(&format!("{} helper", p)))
+    }
+    
+    /// 删除和恢复会话依赖 Cursor 的 composerData 结构
+    pub fn supports_cleanup(&self) -> bool {
+        self.chat_keys.contains(&COMPOSER_DATA_KEY)
+    }
+}
+
+pub fn find(id: &str) -> Result<&'static EditorProfile, AppError> {
+    PROFILES
+        .iter()
+        .find(|p| p.id.eq_ignore_ascii_case(id.trim()))
+        .ok_or_else(|| {
+            let ids: Vec<&str> = PROFILES.iter().map(|p| p.id).collect();
+            AppError::NotFound(format!("未知的编辑器 {}，可选: {}", id, ids.join(", ")))
+        })
+}
+
+/// 设置进程内使用的编辑器（命令行参数使用）
+pub fn set_override(id: &str) -> Result<(), AppError> {
+    let profile = find(id)?;
+    *OVERRIDE.write().unwrap_or_else(|e| e.into_inner()) = Some(profile);
+    Ok(())
+}
+
+/// 当前分析的编辑器，优先级：临时切换 > 命令行 > 环境变量 > 设置文件 > Cursor
+pub fn active() -> &'static EditorProfile {
+    if let Some(profile) = SCOPED.with(|s| s.get()) {
+        return profile;
+    }
+    if let Some(profile) = *OVERRIDE.read().unwrap_or_else(|e| e.into_inner()) {
+        return profile;
+    }
+    if let Ok(id) = std::env::var(EDITOR_ENV) {
+        return find(&id).unwrap_or(&PROFILES[0]);
+    }
+    settings::current()
+        .editor
+        .as_deref()
+        .and_then(|id| find(id).ok())
+        .unwrap_or(&PROFILES[0])
+}
+
+/// 在当前线程临时切换编辑器和数据目录执行分析
+fn with_profile<T>(profile: &'static EditorProfile, root: &Path, f: impl FnOnce() -> T) -> T {
+    let previous = SCOPED.with(|s| s.replace(Some(profile)));
+    let result = data_root::with_root(root, f);
+    SCOPED.with(|s| s.set(previous));
+    result
+}
+
+#[derive(Debug, Serialize)]
+pub struct EditorSummary {
+    pub editor: EditorProfile,
+    pub data_root: String,
+    pub installed: bool,
+    pub active: bool,
+    pub storage: Option<StorageInfo>,
+    pub overview: Option<OverviewStats>,
+    pub error: Option<String>,
+}
+
+#[cfg_attr(feature = "gui", tauri::command)]
+pub fn get_editor_profiles() -> Vec<EditorProfile> {
+    PROFILES.to_vec()
+}
+
+#[cfg_attr(feature = "gui", tauri::command)]
+pub fn get_active_editor() -> EditorProfile {
+    active().clone()
+}
+
+/// 切换分析的编辑器并保存到设置文件，之后的 `active` 立即使用新的编辑器
+#[cfg_attr(feature = "gui", tauri::command)]
+pub fn set_editor(editor_id: String) -> Result<EditorProfile, AppError> {
+    let profile = find(&editor_id)?;
+    let mut current = settings::load();
+    current.editor = Some(profile.id.to_string());
+    settings::save(&current)?;
+    Ok(profile.clone())
+}
+
+/// 对本机安装的所有编辑器做存储和会话统计，便于并排对比
+#[cfg_attr(feature = "gui", tauri::command)]
+pub fn get_editor_summaries() -> Vec<EditorSummary> {
+    let current = active();
+    PROFILES
+        .iter()
+        .map(|profile| {
+            let is_active = std::ptr::eq(profile, current);
+            // 当前编辑器遵循自定义数据目录，其他编辑器使用系统默认路径
+            let root = if is_active {
+                data_root::resolve().map(|(path, _)| path)
+            } else {
+                profile.default_user_path()
+            };
+            let mut summary = EditorSummary {
+                editor: profile.clone(),
+                data_root: String::new(),
+                installed: false,
+                active: is_active,
+                storage: None,
+                overview: None,
+                error: None,
+            };
+            
+            let root = match root {
+                Ok(root) => root,
+                Err(e) => {
+                    summary.error = Some(e.to_string());
+                    return summary;
+                }
+            };
+            summary.data_root = root.to_string_lossy().to_string();
+            summary.installed = root.is_dir();
+            if !summary.installed {
+                return summary;
+            }
+            
+            with_profile(profile, &root, || {
+                match crate::get_storage_info() {
+                    Ok(storage) => summary.storage = Some(storage),
+                    Err(e) => summary.error = Some(e),
+                }
+                match crate::get_overview() {
+                    Ok(overview) => summary.overview = Some(overview),
+                    Err(e) => summary.error = Some(e),
+                }
+            });
+            summary
+        })
+        .collect()
+}
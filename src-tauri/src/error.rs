@@ -0,0 +1,59 @@
+use serde::Serialize;
+use std::fmt;
+
+/// 命令返回给前端的结构化错误，序列化为 `{ kind, message }`
+#[derive(Debug, Serialize, Clone)]
+#[serde(tag = "kind", content = "message", rename_all = "snake_case")]
+pub enum AppError {
+    /// 找不到项目、工作区或会话
+    NotFound(String),
+    /// SQLite 读写失败
+    Database(String),
+    /// 垃圾桶写入失败
+    Trash(String),
+    /// JSON 数据无法解析
+    InvalidData(String),
+    /// Cursor 正在运行或数据库被占用
+    CursorRunning(String),
+    /// 文件读写失败
+    Io(String),
+}
+
+impl fmt::Display for AppError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AppError::NotFound(msg) => write!(f, "{}", msg),
+            AppError::Database(msg) => write!(f, "数据库错误: {}", msg),
+            AppError::Trash(msg) => write!(f, "写入垃圾桶失败: {}", msg),
+            AppError::InvalidData(msg) => write!(f, "数据格式错误: {}", msg),
+            AppError::CursorRunning(msg) => write!(f, "{}", msg),
+            AppError::Io(msg) => write!(f, "文件操作失败: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for AppError {}
+
+impl From<rusqlite::Error> for AppError {
+    fn from(e: rusqlite::Error) -> Self {
+        AppError::Database(e.to_string())
+    }
+}
+
+impl From<std::io::Error> for AppError {
+    fn from(e: std::io::Error) -> Self {
+        AppError::Io(e.to_string())
+    }
+}
+
+impl From<serde_json::Error> for AppError {
+    fn from(e: serde_json::Error) -> Self {
+        AppError::InvalidData(e.to_string())
+    }
+}
+
+impl From<AppError> for String {
+    fn from(e: AppError) -> Self {
+        e.to_string()
+    }
+}
@@ -0,0 +1,262 @@
+//! User/History 分析：本地文件历史（每个资源一个目录，目录中是 entries.json 和各版本的快照文件）
+
+use crate::workspace_uri::{self, WorkspaceLocation};
+use crate::AppError;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// 不属于任何已知项目的文件归到这一组
+const UNKNOWN_PROJECT: &str = "[未归属项目]";
+
+/// entries.json 中的一个历史版本
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct HistoryEntry {
+    pub id: String,
+    pub timestamp: i64,
+    pub source: Option<String>,
+    /// 快照文件大小，文件缺失时为 0
+    pub size: u64,
+}
+
+/// 一个资源（源文件）的历史目录
+#[derive(Debug, Clone)]
+pub(crate) struct HistoryDir {
+    pub dir: PathBuf,
+    pub resource: String,
+    pub entries: Vec<HistoryEntry>,
+    /// entries.json 原始内容，改写时保留其他字段
+    pub raw: serde_json::Value,
+    pub entries_json_size: u64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct HistoryFile {
+    /// History 下的目录名
+    pub dir: String,
+    pub resource: String,
+    pub path: String,
+    pub project_path: String,
+    pub location: WorkspaceLocation,
+    pub entry_count: i64,
+    pub total_size: u64,
+    pub oldest: Option<String>,
+    pub newest: Option<String>,
+    /// 源文件是否还存在，远程文件无法检查时为 None
+    pub exists: Option<bool>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct HistoryProject {
+    pub project_path: String,
+    pub name: String,
+    pub exists: Option<bool>,
+    pub file_count: i64,
+    pub missing_file_count: i64,
+    pub entry_count: i64,
+    pub total_size: u64,
+    pub total_size_human: String,
+    pub oldest: Option<String>,
+    pub newest: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct HistoryReport {
+    pub total_size: u64,
+    pub total_size_human: String,
+    pub file_count: i64,
+    pub entry_count: i64,
+    /// 源文件已不存在的历史
+    pub missing_file_count: i64,
+    pub missing_size: u64,
+    pub projects: Vec<HistoryProject>,
+    pub files: Vec<HistoryFile>,
+}
+
+pub(crate) fn history_root() -> Result<PathBuf, AppError> {
+    Ok(crate::get_cursor_user_path()?.join("History"))
+}
+
+/// 读取一个历史目录，没有 entries.json 或格式不对时返回 None
+pub(crate) fn read_history_dir(dir: &Path) -> Option<HistoryDir> {
+    let entries_json = dir.join("entries.json");
+    let content = fs::read_to_string(&entries_json).ok()?;
+    let raw: serde_json::Value = serde_json::from_str(&content).ok()?;
+    let resource = raw.get("resource")?.as_str()?.to_string();
+    
+    let entries = raw
+        .get("entries")
+        .and_then(|v| v.as_array())
+        .map(|items| {
+            items
+                .iter()
+                .filter_map(|e| {
+                    let id = e.get("id")?.as_str()?.to_string();
+                    let size = fs::metadata(dir.join(&id)).map(|m| m.len()).unwrap_or(0);
+                    Some(HistoryEntry {
+                        timestamp: e.get("timestamp").and_then(|v| v.as_i64()).unwrap_or(0),
+                        source: e.get("source").and_then(|v| v.as_str()).map(|s| s.to_string()),
+                        id,
+                        size,
+                    })
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    
+    Some(HistoryDir {
+        dir: dir.to_path_buf(),
+        resource,
+        entries,
+        raw,
+        entries_json_size: content.len() as u64,
+    })
+}
+
+pub(crate) fn scan_history() -> Result<Vec<HistoryDir>, AppError> {
+    let root = history_root()?;
+    let Ok(entries) = fs::read_dir(&root) else {
+        return Ok(Vec::new());
+    };
+    Ok(entries
+        .filter_map(|e| e.ok())
+        .filter(|e| e.path().is_dir())
+        .filter_map(|e| read_history_dir(&e.path()))
+        .collect())
+}
+
+/// 从 workspaceStorage 收集所有已知的项目路径（包括多项目工作区中的文件夹）
+pub(crate) fn known_project_paths() -> Result<Vec<String>, AppError> {
+    let workspace_storage = crate::get_cursor_user_path()?.join("workspaceStorage");
+    let mut paths = Vec::new();
+    
+    let Ok(entries) = fs::read_dir(&workspace_storage) else {
+        return Ok(paths);
+    };
+    for entry in entries.filter_map(|e| e.ok()) {
+        let Some(data) = fs::read_to_string(entry.path().join("workspace.json"))
+            .ok()
+            .and_then(|content| serde_json::from_str::<serde_json::Value>(&content).ok())
+        else {
+            continue;
+        };
+        if let Some(folder) = data.get("folder").and_then(|v| v.as_str()) {
+            paths.push(workspace_uri::uri_to_path(folder));
+        } else if let Some(workspace) = data.get("workspace").and_then(|v| v.as_str()) {
+            paths.extend(crate::get_workspace_projects(&PathBuf::from(workspace_uri::uri_to_path(workspace))));
+        }
+    }
+    
+    // 长路径优先，嵌套项目归到最内层
+    paths.sort_by(|a, b| b.len().cmp(&a.len()).then_with(|| a.cmp(b)));
+    paths.dedup();
+    Ok(paths)
+}
+
+/// 文件所属的项目：以项目路径为前缀的最长匹配
+pub(crate) fn project_for_path<'a>(path: &str, projects: &'a [String]) -> Option<&'a String> {
+    projects.iter().find(|p| {
+        match (path.get(..p.len()), path.get(p.len()..)) {
+            (Some(prefix), Some(rest)) => workspace_uri::same_path(prefix, p) && rest.starts_with(['/', '\\']),
+            _ => false,
+        }
+    })
+}
+
+/// 本地文件检查是否存在，远程文件无法检查
+pub(crate) fn path_exists(path: &str, location: &WorkspaceLocation) -> Option<bool> {
+    match location {
+        WorkspaceLocation::Local => Some(Path::new(path).exists()),
+        _ => None,
+    }
+}
+
+impl HistoryDir {
+    pub fn total_size(&self) -> u64 {
+        self.entries.iter().map(|e| e.size).sum::<u64>() + self.entries_json_size
+    }
+    
+    fn to_file(&self, projects: &[String]) -> HistoryFile {
+        let path = workspace_uri::uri_to_path(&self.resource);
+        let location = workspace_uri::parse_location(&self.resource);
+        let project_path = project_for_path(&path, projects)
+            .cloned()
+            .unwrap_or_else(|| UNKNOWN_PROJECT.to_string());
+        let oldest = self.entries.iter().map(|e| e.timestamp).min();
+        let newest = self.entries.iter().map(|e| e.timestamp).max();
+        
+        HistoryFile {
+            dir: self.dir.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default(),
+            resource: self.resource.clone(),
+            exists: path_exists(&path, &location),
+            path,
+            project_path,
+            location,
+            entry_count: self.entries.len() as i64,
+            total_size: self.total_size(),
+            oldest: oldest.and_then(crate::timestamp_to_string),
+            newest: newest.and_then(crate::timestamp_to_string),
+        }
+    }
+}
+
+#[cfg_attr(feature = "gui", tauri::command)]
+pub fn get_history_report() -> Result<HistoryReport, AppError> {
+    let projects = known_project_paths()?;
+    let mut files: Vec<HistoryFile> = scan_history()?.iter().map(|d| d.to_file(&projects)).collect();
+    files.sort_by_key(|f| std::cmp::Reverse(f.total_size));
+    
+    let mut by_project: HashMap<String, HistoryProject> = HashMap::new();
+    for f in &files {
+        let project = by_project.entry(f.project_path.clone()).or_insert_with(|| HistoryProject {
+            name: workspace_uri::path_name(&f.project_path),
+            exists: if f.project_path == UNKNOWN_PROJECT {
+                None
+            } else {
+                path_exists(&f.project_path, &f.location)
+            },
+            project_path: f.project_path.clone(),
+            file_count: 0,
+            missing_file_count: 0,
+            entry_count: 0,
+            total_size: 0,
+            total_size_human: String::new(),
+            oldest: None,
+            newest: None,
+        });
+        project.file_count += 1;
+        project.entry_count += f.entry_count;
+        project.total_size += f.total_size;
+        if f.exists == Some(false) {
+            project.missing_file_count += 1;
+        }
+        // 时间字符串格式固定，可以直接比较
+        if f.oldest.is_some() && (project.oldest.is_none() || f.oldest < project.oldest) {
+            project.oldest = f.oldest.clone();
+        }
+        if f.newest > project.newest {
+            project.newest = f.newest.clone();
+        }
+    }
+    
+    let mut projects: Vec<HistoryProject> = by_project.into_values().collect();
+    for p in &mut projects {
+        p.total_size_human = crate::format_size(p.total_size);
+    }
+    projects.sort_by_key(|p| std::cmp::Reverse(p.total_size));
+    
+    let missing: Vec<&HistoryFile> = files.iter().filter(|f| f.exists == Some(false)).collect();
+    let total_size: u64 = files.iter().map(|f| f.total_size).sum();
+    
+    Ok(HistoryReport {
+        total_size,
+        total_size_human: crate::format_size(total_size),
+        file_count: files.len() as i64,
+        entry_count: files.iter().map(|f| f.entry_count).sum(),
+        missing_file_count: missing.len() as i64,
+        missing_size: missing.iter().map(|f| f.total_size).sum(),
+        projects,
+        files,
+    })
+}
@@ -0,0 +1,401 @@
+//! History 清理：按时间、每个文件保留的版本数、已删除的文件或项目清理本地文件历史。
+//! 清理的快照和对应的 entries.json 条目移动到应用自己的回收区，可以整批恢复或彻底删除。
+
+use crate::history::{self, HistoryDir};
+use crate::{cursor_guard, workspace_uri, AppError};
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const MANIFEST_FILE: &str = "manifest.json";
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(default)]
+pub struct HistoryCleanupPolicy {
+    /// 删除早于 N 天的版本
+    pub older_than_days: Option<u32>,
+    /// 每个文件最多保留最近 K 个版本
+    pub keep_versions: Option<usize>,
+    /// 删除源文件已不存在的历史
+    pub deleted_files: bool,
+    /// 删除所属项目已不存在的历史
+    pub deleted_projects: bool,
+    /// 只列出将要删除的内容，不做修改
+    pub dry_run: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum RemovalReason {
+    Expired,
+    ExcessVersion,
+    FileDeleted,
+    ProjectDeleted,
+}
+
+impl RemovalReason {
+    pub fn label(&self) -> &'static str {
+        match self {
+            RemovalReason::Expired => "过期",
+            RemovalReason::ExcessVersion => "超出保留版本数",
+            RemovalReason::FileDeleted => "文件已删除",
+            RemovalReason::ProjectDeleted => "项目已删除",
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct HistoryRemoval {
+    pub dir: String,
+    pub path: String,
+    pub entry_id: String,
+    pub timestamp: Option<String>,
+    pub size: u64,
+    pub reason: RemovalReason,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct HistoryCleanupResult {
+    pub dry_run: bool,
+    /// 回收区批次 ID，dry-run 或没有可清理内容时为 None
+    pub batch_id: Option<String>,
+    pub file_count: i64,
+    pub removed_count: i64,
+    pub bytes_saved: u64,
+    pub bytes_saved_human: String,
+    pub removals: Vec<HistoryRemoval>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct HistoryTrashBatch {
+    pub id: String,
+    pub created_at: String,
+    pub policy: HistoryCleanupPolicy,
+    pub file_count: i64,
+    pub entry_count: i64,
+    pub size: u64,
+    pub size_human: String,
+}
+
+/// 回收区放在本工具的数据目录下，不占用编辑器的 User 目录
+fn history_trash_dir() -> Result<PathBuf, AppError> {
+    Ok(crate::get_app_data_path()?.join("history-trash"))
+}
+
+fn batch_dir(batch_id: &str) -> Result<PathBuf, AppError> {
+    // 批次 ID 是时间戳，拒绝带路径分隔符的参数
+    if batch_id.is_empty() || !batch_id.chars().all(|c| c.is_ascii_alphanumeric() || c == '-') {
+        return Err(AppError::InvalidData(format!("无效的批次 ID: {}", batch_id)));
+    }
+    let dir = history_trash_dir()?.join(batch_id);
+    if !dir.is_dir() {
+        return Err(AppError::NotFound(format!("找不到历史回收批次 {}", batch_id)));
+    }
+    Ok(dir)
+}
+
+/// 按策略挑出一个历史目录中要删除的版本
+fn select_removals(
+    dir: &HistoryDir,
+    policy: &HistoryCleanupPolicy,
+    projects: &[String],
+    cutoff: Option<i64>,
+) -> Vec<HistoryRemoval> {
+    let path = workspace_uri::uri_to_path(&dir.resource);
+    let location = workspace_uri::parse_location(&dir.resource);
+    let dir_name = dir.dir.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+    
+    let project_deleted = history::project_for_path(&path, projects)
+        .is_some_and(|p| history::path_exists(p, &location) == Some(false));
+    let whole = if policy.deleted_projects && project_deleted {
+        Some(RemovalReason::ProjectDeleted)
+    } else if policy.deleted_files && history::path_exists(&path, &location) == Some(false) {
+        Some(RemovalReason::FileDeleted)
+    } else {
+        None
+    };
+    
+    let mut entries: Vec<_> = dir.entries.iter().collect();
+    entries.sort_by_key(|e| std::cmp::Reverse(e.timestamp));
+    
+    entries
+        .into_iter()
+        .enumerate()
+        .filter_map(|(index, e)| {
+            let reason = whole.or_else(|| {
+                if policy.keep_versions.is_some_and(|k| index >= k) {
+                    Some(RemovalReason::ExcessVersion)
+                } else if cutoff.is_some_and(|c| e.timestamp < c) {
+                    Some(RemovalReason::Expired)
+                } else {
+                    None
+                }
+            })?;
+            Some(HistoryRemoval {
+                dir: dir_name.clone(),
+                path: path.clone(),
+                entry_id: e.id.clone(),
+                timestamp: crate::timestamp_to_string(e.timestamp),
+                size: e.size,
+                reason,
+            })
+        })
+        .collect()
+}
+
+/// 用 entries 替换 entries.json 中的条目，先写临时文件再改名
+fn write_entries(path: &Path, raw: &serde_json::Value, entries: Vec<serde_json::Value>) -> Result<(), AppError> {
+    let mut raw = raw.clone();
+    raw["entries"] = serde_json::Value::Array(entries);
+    let tmp = path.with_extension("json.tmp");
+    fs::write(&tmp, serde_json::to_string(&raw)?)?;
+    fs::rename(&tmp, path)?;
+    Ok(())
+}
+
+fn raw_entries(raw: &serde_json::Value) -> Vec<serde_json::Value> {
+    raw.get("entries").and_then(|v| v.as_array()).cloned().unwrap_or_default()
+}
+
+fn entry_id(entry: &serde_json::Value) -> Option<&str> {
+    entry.get("id").and_then(|v| v.as_str())
+}
+
+/// 清理过程中已经完成的一步，出错时倒序撤销
+enum Undo {
+    Moved { from: PathBuf, to: PathBuf },
+    Rewrote { path: PathBuf, raw: serde_json::Value },
+}
+
+/// 撤销已完成的步骤，全部撤销成功时返回 true
+fn rollback(done: Vec<Undo>) -> bool {
+    let mut ok = true;
+    for step in done.into_iter().rev() {
+        ok &= match step {
+            Undo::Moved { from, to } => crate::move_path(&to, &from).is_ok(),
+            Undo::Rewrote { path, raw } => write_entries(&path, &raw, raw_entries(&raw)).is_ok(),
+        };
+    }
+    ok
+}
+
+/// 把一个历史目录中选中的版本移动到回收区，全部版本都删除时整个目录一起移走。
+/// 先改写 entries.json 再移动快照，中途失败时 History 中不会留下指向已移走快照的条目
+fn move_to_trash(dir: &HistoryDir, removed: &HashSet<&str>, trash: &Path, done: &mut Vec<Undo>) -> Result<(), AppError> {
+    let target = trash.join(dir.dir.file_name().unwrap_or_default());
+    let (gone, kept): (Vec<_>, Vec<_>) = raw_entries(&dir.raw)
+        .into_iter()
+        .partition(|e| entry_id(e).is_some_and(|id| removed.contains(id)));
+    
+    if kept.is_empty() {
+        crate::move_path(&dir.dir, &target)?;
+        done.push(Undo::Moved { from: dir.dir.clone(), to: target });
+        return Ok(());
+    }
+    
+    fs::create_dir_all(&target)?;
+    // 回收区中的 entries.json 只记录被移走的条目，恢复时合并回去
+    write_entries(&target.join("entries.json"), &dir.raw, gone)?;
+    let entries_json = dir.dir.join("entries.json");
+    write_entries(&entries_json, &dir.raw, kept)?;
+    done.push(Undo::Rewrote { path: entries_json, raw: dir.raw.clone() });
+    
+    for id in removed {
+        let src = dir.dir.join(id);
+        if src.exists() {
+            let dst = target.join(id);
+            crate::move_path(&src, &dst)?;
+            done.push(Undo::Moved { from: src, to: dst });
+        }
+    }
+    Ok(())
+}
+
+#[cfg_attr(feature = "gui", tauri::command)]
+pub fn clean_history(policy: HistoryCleanupPolicy) -> Result<HistoryCleanupResult, AppError> {
+    if policy.older_than_days.is_none()
+        && policy.keep_versions.is_none()
+        && !policy.deleted_files
+        && !policy.deleted_projects
+    {
+        return Err(AppError::InvalidData("至少需要指定一个清理条件".to_string()));
+    }
+    if !policy.dry_run {
+        cursor_guard::ensure_not_running()?;
+    }
+    
+    let projects = history::known_project_paths()?;
+    let cutoff = policy
+        .older_than_days
+        .map(|days| Utc::now().timestamp_millis() - days as i64 * 24 * 60 * 60 * 1000);
+    
+    let mut plan: Vec<(HistoryDir, Vec<HistoryRemoval>)> = history::scan_history()?
+        .into_iter()
+        .map(|dir| {
+            let removals = select_removals(&dir, &policy, &projects, cutoff);
+            (dir, removals)
+        })
+        .filter(|(_, removals)| !removals.is_empty())
+        .collect();
+    plan.sort_by(|a, b| a.0.dir.cmp(&b.0.dir));
+    
+    let mut result = HistoryCleanupResult {
+        dry_run: policy.dry_run,
+        batch_id: None,
+        file_count: plan.len() as i64,
+        removed_count: 0,
+        bytes_saved: 0,
+        bytes_saved_human: String::new(),
+        removals: Vec::new(),
+    };
+    
+    for (dir, removals) in &plan {
+        result.removed_count += removals.len() as i64;
+        result.bytes_saved += removals.iter().map(|r| r.size).sum::<u64>();
+        // 整个目录移走时 entries.json 也一并释放
+        if removals.len() == dir.entries.len() {
+            result.bytes_saved += dir.entries_json_size;
+        }
+    }
+    result.bytes_saved_human = crate::format_size(result.bytes_saved);
+    
+    if !policy.dry_run && !plan.is_empty() {
+        let batch_id = Utc::now().format("%Y%m%d%H%M%S%3f").to_string();
+        let trash = history_trash_dir()?.join(&batch_id);
+        fs::create_dir_all(&trash)?;
+        
+        // 先记录批次，移动中途中断时回收区里也能看到这一批
+        let manifest = serde_json::json!({
+            "created_at": Utc::now().format("%Y-%m-%d %H:%M:%S").to_string(),
+            "policy": policy,
+            "file_count": result.file_count,
+            "entry_count": result.removed_count,
+        });
+        if let Err(e) = fs::write(trash.join(MANIFEST_FILE), serde_json::to_string_pretty(&manifest)?) {
+            let _ = fs::remove_dir_all(&trash);
+            return Err(e.into());
+        }
+        
+        // 任一目录失败时撤销已经移动的内容；撤销不完整时保留回收批次，避免丢失数据
+        let mut done = Vec::new();
+        for (dir, removals) in &plan {
+            let removed: HashSet<&str> = removals.iter().map(|r| r.entry_id.as_str()).collect();
+            if let Err(e) = move_to_trash(dir, &removed, &trash, &mut done) {
+                if rollback(done) {
+                    let _ = fs::remove_dir_all(&trash);
+                }
+                return Err(e);
+            }
+        }
+        result.batch_id = Some(batch_id);
+    }
+    
+    result.removals = plan.into_iter().flat_map(|(_, removals)| removals).collect();
+    Ok(result)
+}
+
+#[cfg_attr(feature = "gui", tauri::command)]
+pub fn list_history_trash() -> Result<Vec<HistoryTrashBatch>, AppError> {
+    let root = history_trash_dir()?;
+    let Ok(entries) = fs::read_dir(&root) else {
+        return Ok(Vec::new());
+    };
+    
+    let mut batches: Vec<HistoryTrashBatch> = entries
+        .filter_map(|e| e.ok())
+        .filter(|e| e.path().is_dir())
+        .map(|e| {
+            let path = e.path();
+            let manifest: serde_json::Value = fs::read_to_string(path.join(MANIFEST_FILE))
+                .ok()
+                .and_then(|content| serde_json::from_str(&content).ok())
+                .unwrap_or_default();
+            let size = crate::get_dir_size(&path);
+            HistoryTrashBatch {
+                id: e.file_name().to_string_lossy().to_string(),
+                created_at: manifest.get("created_at").and_then(|v| v.as_str()).unwrap_or("").to_string(),
+                policy: manifest
+                    .get("policy")
+                    .and_then(|v| serde_json::from_value(v.clone()).ok())
+                    .unwrap_or_default(),
+                file_count: manifest.get("file_count").and_then(|v| v.as_i64()).unwrap_or(0),
+                entry_count: manifest.get("entry_count").and_then(|v| v.as_i64()).unwrap_or(0),
+                size,
+                size_human: crate::format_size(size),
+            }
+        })
+        .collect();
+    batches.sort_by(|a, b| b.id.cmp(&a.id));
+    Ok(batches)
+}
+
+/// 把回收区中的一个目录放回 History，原目录还在时合并 entries.json
+fn restore_dir(src: &Path, target: &Path) -> Result<(), AppError> {
+    if !target.exists() {
+        crate::move_path(src, target)?;
+        return Ok(());
+    }
+    
+    let trashed: serde_json::Value = serde_json::from_str(&fs::read_to_string(src.join("entries.json"))?)?;
+    let current: serde_json::Value = serde_json::from_str(&fs::read_to_string(target.join("entries.json"))?)?;
+    
+    let mut entries = raw_entries(&current);
+    let existing: HashSet<String> = entries.iter().filter_map(entry_id).map(|s| s.to_string()).collect();
+    for entry in raw_entries(&trashed) {
+        let Some(id) = entry_id(&entry).map(|s| s.to_string()) else {
+            continue;
+        };
+        if existing.contains(&id) {
+            continue;
+        }
+        let snapshot = src.join(&id);
+        if snapshot.exists() && !target.join(&id).exists() {
+            crate::move_path(&snapshot, &target.join(&id))?;
+        }
+        entries.push(entry);
+    }
+    entries.sort_by_key(|e| e.get("timestamp").and_then(|v| v.as_i64()).unwrap_or(0));
+    
+    write_entries(&target.join("entries.json"), &current, entries)?;
+    fs::remove_dir_all(src)?;
+    Ok(())
+}
+
+/// 恢复一个清理批次，返回恢复的文件数量
+#[cfg_attr(feature = "gui", tauri::command)]
+pub fn restore_history_trash(batch_id: String) -> Result<i64, AppError> {
+    cursor_guard::ensure_not_running()?;
+    let batch = batch_dir(&batch_id)?;
+    let history_root = history::history_root()?;
+    fs::create_dir_all(&history_root)?;
+    
+    let mut restored = 0i64;
+    for entry in fs::read_dir(&batch)?.filter_map(|e| e.ok()) {
+        let src = entry.path();
+        if !src.is_dir() {
+            continue;
+        }
+        restore_dir(&src, &history_root.join(entry.file_name()))?;
+        restored += 1;
+    }
+    
+    fs::remove_dir_all(&batch)?;
+    Ok(restored)
+}
+
+/// 彻底删除回收区中的批次，不指定批次时清空整个回收区
+#[cfg_attr(feature = "gui", tauri::command)]
+pub fn purge_history_trash(batch_id: Option<String>) -> Result<u64, AppError> {
+    let dir = match batch_id {
+        Some(id) => batch_dir(&id)?,
+        None => history_trash_dir()?,
+    };
+    if !dir.exists() {
+        return Ok(0);
+    }
+    let size = crate::get_dir_size(&dir);
+    fs::remove_dir_all(&dir)?;
+    Ok(size)
+}
@@ -0,0 +1,257 @@
+//! 按 key 前缀统计 cursorDiskKV 和 ItemTable：前缀取第一个 `:` 或 `.` 之前的部分，
+//! 例如 `bubbleId:<composerId>:<bubbleId>` → `bubbleId`，`workbench.panel.x` → `workbench`。
+//!
+//! 每张表只扫描一遍，同时记录每个前缀和整张表中最大的 N 个 key。
+
+use crate::AppError;
+use rusqlite::Connection;
+use serde::{Deserialize, Serialize};
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap};
+use std::path::Path;
+
+/// 默认返回的最大 key 数量
+pub const DEFAULT_TOP_N: usize = 10;
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct KeySize {
+    pub size: u64,
+    pub key: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct KeyPrefixStats {
+    pub prefix: String,
+    pub count: i64,
+    pub total_size: u64,
+    pub total_size_human: String,
+    pub avg_size: u64,
+    pub max_size: u64,
+    /// 该前缀下最大的 key，按大小降序
+    pub top_keys: Vec<KeySize>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct TableKeyStats {
+    pub table: String,
+    pub count: i64,
+    pub total_size: u64,
+    pub total_size_human: String,
+    /// 按总大小降序
+    pub prefixes: Vec<KeyPrefixStats>,
+    pub top_keys: Vec<KeySize>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct KeyBreakdown {
+    pub db_path: String,
+    pub tables: Vec<TableKeyStats>,
+}
+
+/// 只保留最大的 N 个 key 的小顶堆
+struct TopKeys {
+    limit: usize,
+    heap: BinaryHeap<Reverse<KeySize>>,
+}
+
+impl TopKeys {
+    fn new(limit: usize) -> Self {
+        TopKeys { limit, heap: BinaryHeap::new() }
+    }
+    
+    fn push(&mut self, key: &str, size: u64) {
+        if self.limit == 0 {
+            return;
+        }
+        if self.heap.len() == self.limit {
+            match self.heap.peek() {
+                Some(Reverse(min)) if min.size >= size => return,
+                _ => {
+                    self.heap.pop();
+                }
+            }
+        }
+        self.heap.push(Reverse(KeySize { size, key: key.to_string() }));
+    }
+    
+    fn into_sorted(self) -> Vec<KeySize> {
+        // Reverse 的升序即大小的降序
+        self.heap.into_sorted_vec().into_iter().map(|Reverse(k)| k).collect()
+    }
+}
+
+struct PrefixAccumulator {
+    count: i64,
+    total_size: u64,
+    max_size: u64,
+    top: TopKeys,
+}
+
+pub fn key_prefix(key: &str) -> &str {
+    key.find([':', '.']).map(|i| &key[..i]).unwrap_or(key)
+}
+
+pub(crate) fn table_exists(conn: &Connection, table: &str) -> Result<bool, AppError> {
+    Ok(conn
+        .prepare("SELECT 1 FROM sqlite_master WHERE type = 'table' AND name = ?1")?
+        .exists([table])?)
+}
+
+/// 扫描一张表的全部 key，value 大小按字节计算（TEXT 也转成 BLOB 再取长度）
+pub(crate) fn table_key_stats(conn: &Connection, table: &str, top_n: usize) -> Result<TableKeyStats, AppError> {
+    let mut stmt = conn.prepare(&format!(
+        "SELECT key, LENGTH(CAST(value AS BLOB)) FROM {}",
+        table
+    ))?;
+    let mut rows = stmt.query([])?;
+    
+    let mut prefixes: HashMap<String, PrefixAccumulator> = HashMap::new();
+    let mut top = TopKeys::new(top_n);
+    let mut count = 0i64;
+    let mut total_size = 0u64;
+    
+    while let Some(row) = rows.next()? {
+        let Ok(key) = row.get::<_, String>(0) else {
+            continue;
+        };
+        let size = row.get::<_, Option<i64>>(1)?.unwrap_or(0) as u64;
+        
+        count += 1;
+        total_size += size;
+        top.push(&key, size);
+        
+        let acc = prefixes.entry(key_prefix(&key).to_string()).or_insert_with(|| PrefixAccumulator {
+            count: 0,
+            total_size: 0,
+            max_size: 0,
+            top: TopKeys::new(top_n),
+        });
+        acc.count += 1;
+        acc.total_size += size;
+        acc.max_size = acc.max_size.max(size);
+        acc.top.push(&key, size);
+    }
+    
+    let mut prefixes: Vec<KeyPrefixStats> = prefixes
+        .into_iter()
+        .map(|(prefix, acc)| KeyPrefixStats {
+            prefix,
+            count: acc.count,
+            total_size: acc.total_size,
+            total_size_human: crate::format_size(acc.total_size),
+            avg_size: acc.total_size / acc.count.max(1) as u64,
+            max_size: acc.max_size,
+            top_keys: acc.top.into_sorted(),
+        })
+        .collect();
+    prefixes.sort_by(|a, b| b.total_size.cmp(&a.total_size).then_with(|| a.prefix.cmp(&b.prefix)));
+    
+    Ok(TableKeyStats {
+        table: table.to_string(),
+        count,
+        total_size,
+        total_size_human: crate::format_size(total_size),
+        prefixes,
+        top_keys: top.into_sorted(),
+    })
+}
+
+impl TableKeyStats {
+    pub fn prefix(&self, prefix: &str) -> Option<&KeyPrefixStats> {
+        self.prefixes.iter().find(|p| p.prefix == prefix)
+    }
+}
+
+/// 统计一个数据库中 cursorDiskKV 和 ItemTable 的 key，不存在的表跳过
+pub(crate) fn database_key_stats(db_path: &Path, top_n: usize) -> Result<KeyBreakdown, AppError> {
+    let conn = Connection::open(db_path)?;
+    let mut tables = Vec::new();
+    for table in ["cursorDiskKV", "ItemTable"] {
+        if table_exists(&conn, table)? {
+            tables.push(table_key_stats(&conn, table, top_n)?);
+        }
+    }
+    Ok(KeyBreakdown {
+        db_path: db_path.to_string_lossy().to_string(),
+        tables,
+    })
+}
+
+/// 按前缀统计全局库或指定工作区库中的 key
+#[cfg_attr(feature = "gui", tauri::command)]
+pub fn get_key_breakdown(workspace_id: Option<String>, top_n: Option<usize>) -> Result<KeyBreakdown, AppError> {
+    let db_path = match &workspace_id {
+        Some(id) => {
+            crate::validate_workspace_id(id)?;
+            let path = crate::get_cursor_user_path()?
+                .join("workspaceStorage")
+                .join(id)
+                .join("state.vscdb");
+            if !path.exists() {
+                return Err(AppError::NotFound(format!("未找到工作区 {} 的数据库", id)));
+            }
+            path
+        }
+        None => crate::get_global_db_path()?,
+    };
+    if !db_path.exists() {
+        return Err(AppError::NotFound(format!("数据库 {} 不存在", db_path.display())));
+    }
+    database_key_stats(&db_path, top_n.unwrap_or(DEFAULT_TOP_N))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn key_prefix_splits_at_first_separator() {
+        let cases = [
+            ("bubbleId:1a2b:3c4d", "bubbleId"),
+            ("composerData:1a2b", "composerData"),
+            ("workbench.panel.chat", "workbench"),
+            // `:` 和 `.` 以先出现的为准
+            ("aiService.prompts:v2", "aiService"),
+            ("agentKv:blob.v1", "agentKv"),
+            ("cursorAuth/accessToken", "cursorAuth/accessToken"),
+            (":leading", ""),
+            ("", ""),
+        ];
+        for (key, expected) in cases {
+            assert_eq!(key_prefix(key), expected, "{:?}", key);
+        }
+    }
+    
+    #[test]
+    fn top_keys_keeps_largest() {
+        let mut top = TopKeys::new(2);
+        for (key, size) in [("a", 10), ("b", 30), ("c", 20), ("d", 5)] {
+            top.push(key, size);
+        }
+        let keys: Vec<(u64, String)> = top.into_sorted().into_iter().map(|k| (k.size, k.key)).collect();
+        assert_eq!(keys, [(30, "b".to_string()), (20, "c".to_string())]);
+    }
+    
+    #[test]
+    fn table_key_stats_groups_by_prefix() {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch(
+            "CREATE TABLE ItemTable (key TEXT UNIQUE ON CONFLICT REPLACE, value BLOB);
+             INSERT INTO ItemTable VALUES ('workbench.panel', 'abcd'), ('workbench.view', 'ab'),
+                 ('cursorAuth/token', 'abcdefgh'), ('empty', NULL);",
+        )
+        .unwrap();
+        let stats = table_key_stats(&conn, "ItemTable", 1).unwrap();
+        
+        assert_eq!(stats.count, 4);
+        assert_eq!(stats.total_size, 14);
+        assert_eq!(stats.top_keys[0].key, "cursorAuth/token");
+        let workbench = stats.prefix("workbench").unwrap();
+        assert_eq!((workbench.count, workbench.total_size, workbench.max_size, workbench.avg_size), (2, 6, 4, 3));
+        assert_eq!(workbench.top_keys.len(), 1);
+        assert_eq!(workbench.top_keys[0].key, "workbench.panel");
+        // 按总大小降序
+        let order: Vec<&str> = stats.prefixes.iter().map(|p| p.prefix.as_str()).collect();
+        assert_eq!(order, ["cursorAuth/token", "workbench", "empty"]);
+    }
+}
@@ -0,0 +1,355 @@
+//! 全局库中的孤立会话数据：会话列表（allComposers）保存在各个工作区库里，
+//! 而 composerData、bubble、checkpoint、agentKv 保存在全局库的 cursorDiskKV 中。
+//! 手动删除 workspaceStorage 目录后，这些数据就不再属于任何会话。
+//!
+//! 清理时数据移入垃圾桶，项目路径记为 [`ORPHAN_PROJECT_PATH`]，恢复时只写回全局库。
+
+use crate::transcript::kv_text;
+use crate::{cursor_guard, editor, snapshot, AppError};
+use rusqlite::types::Value;
+use rusqlite::Connection;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::fs;
+
+/// 孤立数据在垃圾桶中的项目路径
+pub const ORPHAN_PROJECT_PATH: &str = "[孤立数据]";
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct OrphanedComposer {
+    pub composer_id: String,
+    /// composerData 中记录的会话名称
+    pub name: Option<String>,
+    pub has_composer_data: bool,
+    pub bubble_count: i64,
+    pub checkpoint_count: i64,
+    pub agent_kv_count: i64,
+    pub size: u64,
+    pub size_human: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct KvOrphanReport {
+    /// 各工作区库中登记的会话数量
+    pub known_composer_count: i64,
+    pub composer_data_count: i64,
+    pub composer_data_size: u64,
+    pub bubble_count: i64,
+    pub bubble_size: u64,
+    pub checkpoint_count: i64,
+    pub checkpoint_size: u64,
+    pub agent_kv_count: i64,
+    pub agent_kv_size: u64,
+    pub total_size: u64,
+    pub total_size_human: String,
+    /// 按大小降序
+    pub composers: Vec<OrphanedComposer>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct KvPurgeResult {
+    pub purged_count: i64,
+    pub kv_count: i64,
+    pub bytes_freed: u64,
+    pub bytes_freed_human: String,
+    pub trash_ids: Vec<i64>,
+}
+
+/// 从 composer.composerData 中取出所有会话 ID（不区分 head 和子会话）
+pub(crate) fn composer_ids(conn: &Connection, ids: &mut HashSet<String>) -> Result<(), AppError> {
+    let value: Option<Value> = conn
+        .query_row("SELECT value FROM ItemTable WHERE key = ?1", [editor::COMPOSER_DATA_KEY], |row| row.get(0))
+        .map(Some)
+        .or_else(|e| match e {
+            rusqlite::Error::QueryReturnedNoRows => Ok(None),
+            e => Err(e),
+        })?;
+    let Some(value) = value.and_then(kv_text) else {
+        return Ok(());
+    };
+    
+    let data: serde_json::Value = serde_json::from_str(&value)?;
+    if let Some(composers) = data.get("allComposers").and_then(|v| v.as_array()) {
+        ids.extend(
+            composers
+                .iter()
+                .filter_map(|c| c.get("composerId").and_then(|v| v.as_str()))
+                .map(|s| s.to_string()),
+        );
+    }
+    Ok(())
+}
+
+/// 收集所有工作区库（以及全局库）中登记的会话 ID。
+/// 任何一个库读取失败都直接报错，避免把还在使用的数据当成孤立数据
+fn known_composer_ids(global: &Connection) -> Result<HashSet<String>, AppError> {
+    let mut ids = HashSet::new();
+    composer_ids(global, &mut ids)?;
+    
+    let workspace_storage = crate::get_cursor_user_path()?.join("workspaceStorage");
+    if let Ok(entries) = fs::read_dir(&workspace_storage) {
+        for entry in entries.filter_map(|e| e.ok()) {
+            let db_path = entry.path().join("state.vscdb");
+            if !db_path.exists() {
+                continue;
+            }
+            let conn = Connection::open(&db_path)?;
+            composer_ids(&conn, &mut ids).map_err(|e| {
+                AppError::InvalidData(format!("无法读取工作区 {} 的会话列表: {}", entry.file_name().to_string_lossy(), e))
+            })?;
+        }
+    }
+    Ok(ids)
+}
+
+/// 会话 ID 是 UUID（8-4-4-4-12 位十六进制）
+fn is_composer_id(id: &str) -> bool {
+    let groups: Vec<&str> = id.split('-').collect();
+    groups.len() == 5
+        && groups.iter().zip([8, 4, 4, 4, 12]).all(|(g, len)| g.len() == len && g.bytes().all(|b| b.is_ascii_hexdigit()))
+}
+
+/// 从 `<前缀>:<会话 ID>:...` 中取出前缀和会话 ID。
+/// 同一前缀下也有不按会话组织的 key（如 `agentKv:blob:<hash>`），第二段不是 UUID 的一律跳过
+fn split_composer_key(key: &str) -> Option<(&str, &str)> {
+    let mut parts = key.split(':');
+    let (prefix, id) = (parts.next()?, parts.next()?);
+    is_composer_id(id).then_some((prefix, id))
+}
+
+fn scan(global: &Connection) -> Result<KvOrphanReport, AppError> {
+    let known = known_composer_ids(global)?;
+    scan_with_known(global, &known)
+}
+
+fn scan_with_known(global: &Connection, known: &HashSet<String>) -> Result<KvOrphanReport, AppError> {
+    let mut report = KvOrphanReport {
+        known_composer_count: known.len() as i64,
+        composer_data_count: 0,
+        composer_data_size: 0,
+        bubble_count: 0,
+        bubble_size: 0,
+        checkpoint_count: 0,
+        checkpoint_size: 0,
+        agent_kv_count: 0,
+        agent_kv_size: 0,
+        total_size: 0,
+        total_size_human: String::new(),
+        composers: Vec::new(),
+    };
+    if !crate::has_cursor_disk_kv(global, "main")? {
+        report.total_size_human = crate::format_size(0);
+        return Ok(report);
+    }
+    
+    let (sql, params) = crate::composer_kv_scan_sql();
+    let mut stmt = global.prepare(&sql)?;
+    let mut rows = stmt.query(rusqlite::params_from_iter(params.iter()))?;
+    
+    let mut orphans: HashMap<String, OrphanedComposer> = HashMap::new();
+    while let Some(row) = rows.next()? {
+        let key: String = row.get(0)?;
+        let size = row.get::<_, Option<i64>>(1)?.unwrap_or(0) as u64;
+        let Some((prefix, id)) = split_composer_key(&key) else {
+            continue;
+        };
+        if known.contains(id) {
+            continue;
+        }
+        
+        let orphan = orphans.entry(id.to_string()).or_insert_with(|| OrphanedComposer {
+            composer_id: id.to_string(),
+            ..Default::default()
+        });
+        orphan.size += size;
+        match prefix {
+            "composerData" => {
+                orphan.has_composer_data = true;
+                report.composer_data_count += 1;
+                report.composer_data_size += size;
+            }
+            "bubbleId" => {
+                orphan.bubble_count += 1;
+                report.bubble_count += 1;
+                report.bubble_size += size;
+            }
+            "checkpointId" => {
+                orphan.checkpoint_count += 1;
+                report.checkpoint_count += 1;
+                report.checkpoint_size += size;
+            }
+            _ => {
+                orphan.agent_kv_count += 1;
+                report.agent_kv_count += 1;
+                report.agent_kv_size += size;
+            }
+        }
+    }
+    
+    for orphan in orphans.values_mut() {
+        orphan.size_human = crate::format_size(orphan.size);
+        if orphan.has_composer_data {
+            orphan.name = composer_data(global, &orphan.composer_id)
+                .and_then(|data| data.get("name").and_then(|v| v.as_str()).map(|s| s.to_string()));
+        }
+    }
+    
+    report.composers = orphans.into_values().collect();
+    report.composers.sort_by(|a, b| b.size.cmp(&a.size).then_with(|| a.composer_id.cmp(&b.composer_id)));
+    report.total_size = report.composers.iter().map(|c| c.size).sum();
+    report.total_size_human = crate::format_size(report.total_size);
+    Ok(report)
+}
+
+fn composer_data(conn: &Connection, composer_id: &str) -> Option<serde_json::Value> {
+    let value: Value = conn
+        .query_row(
+            "SELECT value FROM cursorDiskKV WHERE key = ?1",
+            [format!("composerData:{}", composer_id)],
+            |row| row.get(0),
+        )
+        .ok()?;
+    serde_json::from_str(&kv_text(value)?).ok()
+}
+
+fn open_global() -> Result<Connection, AppError> {
+    let db_path = crate::get_global_db_path()?;
+    if !db_path.exists() {
+        return Err(AppError::NotFound(format!("全局数据库 {} 不存在", db_path.display())));
+    }
+    Ok(Connection::open(&db_path)?)
+}
+
+#[cfg_attr(feature = "gui", tauri::command)]
+pub fn scan_orphaned_kv() -> Result<KvOrphanReport, AppError> {
+    crate::ensure_cleanup_supported()?;
+    scan(&open_global()?)
+}
+
+/// 把孤立数据移入垃圾桶，不指定 ID 时清理全部。清理前重新扫描，只处理仍然孤立的会话
+#[cfg_attr(feature = "gui", tauri::command)]
+pub fn purge_orphaned_kv(composer_ids: Option<Vec<String>>) -> Result<KvPurgeResult, AppError> {
+    crate::ensure_cleanup_supported()?;
+    let global_db = crate::get_global_db_path()?;
+    cursor_guard::ensure_writable(&global_db)?;
+    
+    let mut conn = open_global()?;
+    let report = scan(&conn)?;
+    let targets: Vec<&OrphanedComposer> = report
+        .composers
+        .iter()
+        .filter(|c| composer_ids.as_ref().is_none_or(|ids| ids.contains(&c.composer_id)))
+        .collect();
+    
+    let mut result = KvPurgeResult {
+        purged_count: 0,
+        kv_count: 0,
+        bytes_freed: 0,
+        bytes_freed_human: crate::format_size(0),
+        trash_ids: Vec::new(),
+    };
+    if targets.is_empty() {
+        return Ok(result);
+    }
+    
+    snapshot::take_snapshot(&global_db, "清理孤立数据之前")?;
+    crate::init_trash_db().map_err(AppError::Trash)?;
+    conn.execute("ATTACH DATABASE ?1 AS trash", [crate::get_trash_db_path()?.to_string_lossy()])?;
+    
+    let tx = conn.transaction()?;
+    for orphan in &targets {
+        // 没有 composerData 时用会话 ID 生成一条最小记录，保证垃圾桶中能看到
+        let composer = composer_data(&tx, &orphan.composer_id).unwrap_or_else(|| {
+            serde_json::json!({
+                "composerId": orphan.composer_id,
+                "name": format!("孤立会话 {}", orphan.composer_id),
+            })
+        });
+        let trash_id = crate::add_to_trash(&tx, &composer, ORPHAN_PROJECT_PATH, "", &crate::ComposerPlacement::default())?;
+        crate::move_composer_kv_to_trash(&tx, "main", "global", &orphan.composer_id, trash_id)?;
+        
+        result.trash_ids.push(trash_id);
+        result.purged_count += 1;
+        result.kv_count += orphan.bubble_count
+            + orphan.checkpoint_count
+            + orphan.agent_kv_count
+            + i64::from(orphan.has_composer_data);
+        result.bytes_freed += orphan.size;
+    }
+    tx.commit()?;
+    
+    result.bytes_freed_human = crate::format_size(result.bytes_freed);
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    const KNOWN: &str = "0f8e6c52-1d3a-4b7e-9c2f-5a6b7c8d9e0f";
+    const ORPHAN: &str = "a1b2c3d4-e5f6-4789-abcd-ef0123456789";
+    
+    #[test]
+    fn split_composer_key_requires_uuid() {
+        let cases = [
+            (format!("composerData:{}", ORPHAN), Some(("composerData", ORPHAN))),
+            (format!("bubbleId:{}:5e4d3c2b-1a09-4877-8665-544332211000", ORPHAN), Some(("bubbleId", ORPHAN))),
+            (format!("checkpointId:{}:9", ORPHAN), Some(("checkpointId", ORPHAN))),
+            (format!("agentKv:{}:state", ORPHAN), Some(("agentKv", ORPHAN))),
+            ("agentKv:blob:3f786850e387550fdab836ed7e6dc881de23001b".to_string(), None),
+            ("agentKv:checkpoint:latest".to_string(), None),
+            ("bubbleId:".to_string(), None),
+            ("composerData:a1b2c3d4-e5f6-4789-abcd-ef012345678".to_string(), None),
+            ("composerData:g1b2c3d4-e5f6-4789-abcd-ef0123456789".to_string(), None),
+        ];
+        for (key, expected) in &cases {
+            assert_eq!(split_composer_key(key), *expected, "{}", key);
+        }
+    }
+    
+    #[test]
+    fn scan_ignores_non_composer_keys() {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch("CREATE TABLE cursorDiskKV (key TEXT UNIQUE ON CONFLICT REPLACE, value BLOB)").unwrap();
+        let rows = [
+            format!("composerData:{}", KNOWN),
+            format!("bubbleId:{}:5e4d3c2b-1a09-4877-8665-544332211000", KNOWN),
+            format!("composerData:{}", ORPHAN),
+            format!("bubbleId:{}:5e4d3c2b-1a09-4877-8665-544332211001", ORPHAN),
+            format!("bubbleId:{}:5e4d3c2b-1a09-4877-8665-544332211002", ORPHAN),
+            format!("checkpointId:{}:1", ORPHAN),
+            format!("agentKv:{}:state", ORPHAN),
+            "agentKv:blob:3f786850e387550fdab836ed7e6dc881de23001b".to_string(),
+            "agentKv:blob:89e6c98d92887913cadf06b2adb97f26cde4849b".to_string(),
+        ];
+        for key in &rows {
+            conn.execute("INSERT INTO cursorDiskKV (key, value) VALUES (?1, '{}')", [key]).unwrap();
+        }
+        
+        let known = HashSet::from([KNOWN.to_string()]);
+        let report = scan_with_known(&conn, &known).unwrap();
+        assert_eq!(report.composers.len(), 1);
+        let orphan = &report.composers[0];
+        assert_eq!(orphan.composer_id, ORPHAN);
+        assert!(orphan.has_composer_data);
+        assert_eq!(orphan.bubble_count, 2);
+        assert_eq!(orphan.checkpoint_count, 1);
+        assert_eq!(orphan.agent_kv_count, 1);
+        assert_eq!(report.agent_kv_count, 1);
+    }
+    
+    #[test]
+    fn scan_reads_blob_composer_data() {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch("CREATE TABLE cursorDiskKV (key TEXT UNIQUE ON CONFLICT REPLACE, value BLOB)").unwrap();
+        conn.execute(
+            "INSERT INTO cursorDiskKV (key, value) VALUES (?1, ?2)",
+            rusqlite::params![format!("composerData:{}", ORPHAN), br#"{"name":"Fix OAuth redirect"}"#.to_vec()],
+        )
+        .unwrap();
+        
+        let report = scan_with_known(&conn, &HashSet::new()).unwrap();
+        assert_eq!(report.composers.len(), 1);
+        assert_eq!(report.composers[0].name.as_deref(), Some("Fix OAuth redirect"));
+    }
+}
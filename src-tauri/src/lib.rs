@@ -1,11 +1,41 @@
-use chrono::{TimeZone, Utc};
+use chrono::Utc;
+use rusqlite::types::Value;
 use rusqlite::Connection;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use walkdir::WalkDir;
 
+pub mod activity;
+pub mod chat_mode;
+pub mod compaction;
+pub mod cursor_guard;
+pub mod data_root;
+pub mod deletion_queue;
+pub mod editor;
+pub mod history;
+pub mod history_cleanup;
+pub mod key_stats;
+pub mod kv_orphans;
+mod error;
+pub mod search;
+pub mod settings;
+pub mod snapshot;
+pub mod timezone;
+pub mod transcript;
+pub mod usage_history;
+pub mod watcher;
+pub mod workspace_orphans;
+pub mod workspace_scan;
+pub mod workspace_uri;
+
+use chat_mode::ModeBreakdown;
+use key_stats::KeyPrefixStats;
+use workspace_scan::ScanSnapshot;
+pub use error::AppError;
+pub use workspace_uri::WorkspaceLocation;
+
 // 应用版本号
 const APP_VERSION: &str = env!("CARGO_PKG_VERSION");
 
@@ -25,13 +55,16 @@ pub struct StorageInfo {
     pub state_vscdb_backup_size: u64,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
 pub struct ChatSession {
     pub id: String,
     pub name: String,
     pub mode: String,
     pub created_at: Option<String>,
     pub updated_at: Option<String>,
+    /// 创建和最后更新时间的毫秒时间戳，排序和时间范围过滤都用它们
+    pub created_at_ms: Option<i64>,
+    pub updated_at_ms: Option<i64>,
     pub lines_added: i64,
     pub lines_removed: i64,
     pub files_changed: i64,
@@ -39,16 +72,22 @@ pub struct ChatSession {
     pub branch: String,
     pub is_archived: bool,
     pub subtitle: String,
+    /// 会话在 cursorDiskKV 中占用的字节数（composerData、bubble、checkpoint、agentKv）
+    pub storage_size: u64,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct ProjectStats {
     pub name: String,
     pub path: String,
+    pub location: WorkspaceLocation,
     pub chat_count: i64,
     pub lines_added: i64,
     pub lines_removed: i64,
     pub files_changed: i64,
+    pub storage_size: u64,
+    /// 按模式汇总
+    pub modes: ModeBreakdown,
     pub chats: Vec<ChatSession>,
 }
 
@@ -61,8 +100,10 @@ pub struct WorkspaceInfo {
     pub lines_added: i64,
     pub lines_removed: i64,
     pub files_changed: i64,
+    pub storage_size: u64,
     pub recent_chats: Vec<ChatSession>,
     pub is_multi_project: bool,
+    pub location: WorkspaceLocation,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -79,6 +120,9 @@ pub struct DatabaseStats {
     pub checkpoint_size: u64,
     pub agent_kv_count: i64,
     pub agent_kv_size: u64,
+    /// 按 key 前缀分组的统计，按总大小降序
+    pub item_table_prefixes: Vec<KeyPrefixStats>,
+    pub cursor_disk_kv_prefixes: Vec<KeyPrefixStats>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -90,7 +134,22 @@ pub struct OverviewStats {
     pub net_lines: i64,
     pub total_files_changed: i64,
     pub agent_mode_count: i64,
+    /// agent 以外所有模式的会话数，细分见 `modes`
     pub chat_mode_count: i64,
+    /// 按模式汇总，key 为 unifiedMode 的原始值
+    pub modes: ModeBreakdown,
+    /// 按工作区位置（本地 / SSH / WSL / 容器）汇总
+    pub locations: Vec<LocationUsage>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct LocationUsage {
+    pub location: WorkspaceLocation,
+    pub label: String,
+    pub project_count: i64,
+    pub chat_count: i64,
+    pub lines_added: i64,
+    pub lines_removed: i64,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -100,54 +159,65 @@ pub struct AnalysisResult {
     pub database: DatabaseStats,
     pub projects: Vec<ProjectStats>,
     pub workspaces: Vec<WorkspaceInfo>,
+    /// 长期统计记录失败的原因，不影响本次分析结果
+    pub usage_error: Option<String>,
 }
 
 // ==================== 工具函数 ====================
 
-/// 获取 Cursor 用户数据路径（跨平台）
-fn get_cursor_user_path() -> PathBuf {
-    #[cfg(target_os = "macos")]
-    {
-        let home = dirs::home_dir().expect("Cannot find home directory");
-        home.join("Library/Application Support/Cursor/User")
-    }
-    #[cfg(target_os = "windows")]
-    {
-        let appdata = std::env::var("APPDATA").expect("Cannot find APPDATA");
-        PathBuf::from(appdata).join("Cursor/User")
-    }
-    #[cfg(target_os = "linux")]
-    {
-        let home = dirs::home_dir().expect("Cannot find home directory");
-        home.join(".config/Cursor/User")
-    }
+/// 获取 Cursor 用户数据路径（可通过设置、环境变量或命令行参数指定）
+fn get_cursor_user_path() -> Result<PathBuf, AppError> {
+    data_root::resolve().map(|(path, _)| path)
 }
 
-/// 获取 Cursor 工作区路径（跨平台）
-#[allow(dead_code)]
-fn get_cursor_workspaces_path() -> PathBuf {
-    #[cfg(target_os = "macos")]
-    {
-        let home = dirs::home_dir().expect("Cannot find home directory");
-        home.join("Library/Application Support/Cursor/Workspaces")
+/// 本工具自己的数据目录（快照、统计库等），放在系统数据目录下而不是编辑器的 User 目录里，
+/// 编辑器重置或同步 User 目录时不会一起被清掉。不同的 User 目录各用一个子目录，互不混用
+pub(crate) fn get_app_data_path() -> Result<PathBuf, AppError> {
+    let user_path = get_cursor_user_path()?;
+    let base = dirs::data_dir().ok_or_else(|| AppError::Io("找不到系统数据目录".to_string()))?;
+    let dir = base.join("cursor-analysis").join(format!("{:016x}", stable_hash(&user_path.to_string_lossy())));
+    fs::create_dir_all(&dir)?;
+    Ok(dir)
+}
+
+/// 移动文件或目录。User 目录和本工具的数据目录可能不在同一个文件系统上，
+/// 此时 rename 会失败，改为复制后删除源
+pub(crate) fn move_path(src: &Path, dst: &Path) -> std::io::Result<()> {
+    match fs::rename(src, dst) {
+        Err(e) if e.kind() == std::io::ErrorKind::CrossesDevices => {
+            copy_path(src, dst)?;
+            if src.is_dir() {
+                fs::remove_dir_all(src)
+            } else {
+                fs::remove_file(src)
+            }
+        }
+        result => result,
     }
-    #[cfg(target_os = "windows")]
-    {
-        let appdata = std::env::var("APPDATA").expect("Cannot find APPDATA");
-        PathBuf::from(appdata).join("Cursor/Workspaces")
+}
+
+fn copy_path(src: &Path, dst: &Path) -> std::io::Result<()> {
+    if !src.is_dir() {
+        return fs::copy(src, dst).map(|_| ());
     }
-    #[cfg(target_os = "linux")]
-    {
-        let home = dirs::home_dir().expect("Cannot find home directory");
-        home.join(".config/Cursor/Workspaces")
+    fs::create_dir_all(dst)?;
+    for entry in fs::read_dir(src)? {
+        let entry = entry?;
+        copy_path(&entry.path(), &dst.join(entry.file_name()))?;
     }
+    Ok(())
+}
+
+/// FNV-1a，结果不随 Rust 版本变化，用作目录名
+fn stable_hash(text: &str) -> u64 {
+    text.bytes().fold(0xcbf29ce484222325, |hash, b| (hash ^ b as u64).wrapping_mul(0x100000001b3))
 }
 
-fn format_size(size: u64) -> String {
+pub fn format_size(size: u64) -> String {
     const KB: u64 = 1024;
     const MB: u64 = KB * 1024;
     const GB: u64 = MB * 1024;
-
+    
     if size >= GB {
         format!("{:.2} GB", size as f64 / GB as f64)
     } else if size >= MB {
@@ -159,7 +229,12 @@ fn format_size(size: u64) -> String {
     }
 }
 
-fn get_dir_size(path: &PathBuf) -> u64 {
+/// 获取全局数据库路径
+fn get_global_db_path() -> Result<PathBuf, AppError> {
+    Ok(get_cursor_user_path()?.join("globalStorage/state.vscdb"))
+}
+
+pub(crate) fn get_dir_size(path: &PathBuf) -> u64 {
     WalkDir::new(path)
         .into_iter()
         .filter_map(|e| e.ok())
@@ -169,22 +244,24 @@ fn get_dir_size(path: &PathBuf) -> u64 {
         .sum()
 }
 
+/// 毫秒时间戳按设置的时区格式化
 fn timestamp_to_string(ts: i64) -> Option<String> {
     if ts <= 0 {
         return None;
     }
-    let dt = Utc.timestamp_millis_opt(ts);
-    match dt {
-        chrono::LocalResult::Single(dt) => Some(dt.format("%Y-%m-%d %H:%M").to_string()),
-        _ => None,
-    }
+    timezone::current().format(ts, "%Y-%m-%d %H:%M")
+}
+
+/// 读取毫秒时间戳字段，0 和负数视为没有
+fn timestamp_field(v: &serde_json::Value, key: &str) -> Option<i64> {
+    v.get(key).and_then(|v| v.as_i64()).filter(|ts| *ts > 0)
 }
 
 // ==================== 存储分析 ====================
 
-#[tauri::command]
-fn get_storage_info() -> Result<StorageInfo, String> {
-    let user_path = get_cursor_user_path();
+#[cfg_attr(feature = "gui", tauri::command)]
+pub fn get_storage_info() -> Result<StorageInfo, String> {
+    let user_path = get_cursor_user_path()?;
     
     let global_storage = user_path.join("globalStorage");
     let history = user_path.join("History");
@@ -218,79 +295,41 @@ fn get_storage_info() -> Result<StorageInfo, String> {
 
 // ==================== 数据库分析 ====================
 
-#[tauri::command]
-fn get_database_stats() -> Result<DatabaseStats, String> {
-    let db_path = get_cursor_user_path().join("globalStorage/state.vscdb");
-    
-    let conn = Connection::open(&db_path).map_err(|e| e.to_string())?;
-    
-    // ItemTable 统计
-    let item_count: i64 = conn
-        .query_row("SELECT COUNT(*) FROM ItemTable", [], |row| row.get(0))
-        .unwrap_or(0);
-    
-    let item_size: i64 = conn
-        .query_row("SELECT COALESCE(SUM(LENGTH(value)), 0) FROM ItemTable", [], |row| row.get(0))
-        .unwrap_or(0);
-    
-    // cursorDiskKV 统计
-    let kv_count: i64 = conn
-        .query_row("SELECT COUNT(*) FROM cursorDiskKV", [], |row| row.get(0))
-        .unwrap_or(0);
-    
-    let kv_size: i64 = conn
-        .query_row("SELECT COALESCE(SUM(LENGTH(value)), 0) FROM cursorDiskKV", [], |row| row.get(0))
-        .unwrap_or(0);
-    
-    // bubbleId 统计
-    let bubble_count: i64 = conn
-        .query_row("SELECT COUNT(*) FROM cursorDiskKV WHERE key LIKE 'bubbleId:%'", [], |row| row.get(0))
-        .unwrap_or(0);
-    
-    let bubble_size: i64 = conn
-        .query_row("SELECT COALESCE(SUM(LENGTH(value)), 0) FROM cursorDiskKV WHERE key LIKE 'bubbleId:%'", [], |row| row.get(0))
-        .unwrap_or(0);
-    
-    // composerData 统计
-    let composer_count: i64 = conn
-        .query_row("SELECT COUNT(*) FROM cursorDiskKV WHERE key LIKE 'composerData:%'", [], |row| row.get(0))
-        .unwrap_or(0);
-    
-    let composer_size: i64 = conn
-        .query_row("SELECT COALESCE(SUM(LENGTH(value)), 0) FROM cursorDiskKV WHERE key LIKE 'composerData:%'", [], |row| row.get(0))
-        .unwrap_or(0);
-    
-    // checkpointId 统计
-    let checkpoint_count: i64 = conn
-        .query_row("SELECT COUNT(*) FROM cursorDiskKV WHERE key LIKE 'checkpointId:%'", [], |row| row.get(0))
-        .unwrap_or(0);
-    
-    let checkpoint_size: i64 = conn
-        .query_row("SELECT COALESCE(SUM(LENGTH(value)), 0) FROM cursorDiskKV WHERE key LIKE 'checkpointId:%'", [], |row| row.get(0))
-        .unwrap_or(0);
-    
-    // agentKv 统计
-    let agent_count: i64 = conn
-        .query_row("SELECT COUNT(*) FROM cursorDiskKV WHERE key LIKE 'agentKv:%'", [], |row| row.get(0))
-        .unwrap_or(0);
+#[cfg_attr(feature = "gui", tauri::command)]
+pub fn get_database_stats() -> Result<DatabaseStats, String> {
+    let db_path = get_global_db_path()?;
+    
+    // 每张表扫描一遍，按 key 前缀分组
+    let breakdown = key_stats::database_key_stats(&db_path, key_stats::DEFAULT_TOP_N)?;
+    let table = |name: &str| breakdown.tables.iter().find(|t| t.table == name);
+    let item_table = table("ItemTable");
+    let kv = table("cursorDiskKV");
+    let prefix = |name: &str| {
+        kv.and_then(|t| t.prefix(name))
+            .map(|p| (p.count, p.total_size))
+            .unwrap_or((0, 0))
+    };
     
-    let agent_size: i64 = conn
-        .query_row("SELECT COALESCE(SUM(LENGTH(value)), 0) FROM cursorDiskKV WHERE key LIKE 'agentKv:%'", [], |row| row.get(0))
-        .unwrap_or(0);
+    let (bubble_count, bubble_size) = prefix("bubbleId");
+    let (composer_count, composer_size) = prefix("composerData");
+    let (checkpoint_count, checkpoint_size) = prefix("checkpointId");
+    let (agent_kv_count, agent_kv_size) = prefix("agentKv");
     
     Ok(DatabaseStats {
-        item_table_count: item_count,
-        item_table_size: item_size as u64,
-        cursor_disk_kv_count: kv_count,
-        cursor_disk_kv_size: kv_size as u64,
+        item_table_count: item_table.map(|t| t.count).unwrap_or(0),
+        item_table_size: item_table.map(|t| t.total_size).unwrap_or(0),
+        cursor_disk_kv_count: kv.map(|t| t.count).unwrap_or(0),
+        cursor_disk_kv_size: kv.map(|t| t.total_size).unwrap_or(0),
         bubble_count,
-        bubble_size: bubble_size as u64,
+        bubble_size,
         composer_count,
-        composer_size: composer_size as u64,
+        composer_size,
         checkpoint_count,
-        checkpoint_size: checkpoint_size as u64,
-        agent_kv_count: agent_count,
-        agent_kv_size: agent_size as u64,
+        checkpoint_size,
+        agent_kv_count,
+        agent_kv_size,
+        item_table_prefixes: item_table.map(|t| t.prefixes.clone()).unwrap_or_default(),
+        cursor_disk_kv_prefixes: kv.map(|t| t.prefixes.clone()).unwrap_or_default(),
     })
 }
 
@@ -306,12 +345,16 @@ fn parse_composer_data(json_str: &str) -> Vec<ChatSession> {
                     continue;
                 }
                 
+                let created_at_ms = timestamp_field(c, "createdAt");
+                let updated_at_ms = timestamp_field(c, "lastUpdatedAt");
                 let session = ChatSession {
                     id: c.get("composerId").and_then(|v| v.as_str()).unwrap_or("").to_string(),
                     name: c.get("name").and_then(|v| v.as_str()).unwrap_or("Unnamed").to_string(),
                     mode: c.get("unifiedMode").and_then(|v| v.as_str()).unwrap_or("unknown").to_string(),
-                    created_at: c.get("createdAt").and_then(|v| v.as_i64()).and_then(timestamp_to_string),
-                    updated_at: c.get("lastUpdatedAt").and_then(|v| v.as_i64()).and_then(timestamp_to_string),
+                    created_at: created_at_ms.and_then(timestamp_to_string),
+                    updated_at: updated_at_ms.and_then(timestamp_to_string),
+                    created_at_ms,
+                    updated_at_ms,
                     lines_added: c.get("totalLinesAdded").and_then(|v| v.as_i64()).unwrap_or(0),
                     lines_removed: c.get("totalLinesRemoved").and_then(|v| v.as_i64()).unwrap_or(0),
                     files_changed: c.get("filesChangedCount").and_then(|v| v.as_i64()).unwrap_or(0),
@@ -319,6 +362,7 @@ fn parse_composer_data(json_str: &str) -> Vec<ChatSession> {
                     branch: c.get("createdOnBranch").and_then(|v| v.as_str()).unwrap_or("").to_string(),
                     is_archived: c.get("isArchived").and_then(|v| v.as_bool()).unwrap_or(false),
                     subtitle: c.get("subtitle").and_then(|v| v.as_str()).unwrap_or("").chars().take(100).collect(),
+                    storage_size: 0,
                 };
                 sessions.push(session);
             }
@@ -326,22 +370,146 @@ fn parse_composer_data(json_str: &str) -> Vec<ChatSession> {
     }
     
     // 按更新时间排序
-    sessions.sort_by(|a, b| b.updated_at.cmp(&a.updated_at));
+    sessions.sort_by_key(|s| std::cmp::Reverse(s.updated_at_ms));
+    sessions
+}
+
+/// 解析 VS Code Chat 的 interactive.sessions（会话数组）
+fn parse_interactive_sessions(json_str: &str) -> Vec<ChatSession> {
+    let mut sessions = Vec::new();
+    
+    if let Ok(serde_json::Value::Array(items)) = serde_json::from_str::<serde_json::Value>(json_str) {
+        for s in &items {
+            let requests = s.get("requests").and_then(|v| v.as_array());
+            // 没有自定义标题时用第一条提问作为名称
+            let first_message = requests
+                .and_then(|r| r.first())
+                .and_then(|r| r.get("message"))
+                .and_then(|m| m.get("text"))
+                .and_then(|v| v.as_str())
+                .unwrap_or("");
+            let name = s
+                .get("customTitle")
+                .and_then(|v| v.as_str())
+                .filter(|t| !t.is_empty())
+                .map(|t| t.to_string())
+                .unwrap_or_else(|| first_message.chars().take(50).collect());
+            
+            let created_at_ms = timestamp_field(s, "creationDate");
+            let updated_at_ms = timestamp_field(s, "lastMessageDate");
+            sessions.push(ChatSession {
+                id: s.get("sessionId").and_then(|v| v.as_str()).unwrap_or("").to_string(),
+                name: if name.is_empty() { "Unnamed".to_string() } else { name },
+                mode: "chat".to_string(),
+                created_at: created_at_ms.and_then(timestamp_to_string),
+                updated_at: updated_at_ms.and_then(timestamp_to_string),
+                created_at_ms,
+                updated_at_ms,
+                lines_added: 0,
+                lines_removed: 0,
+                files_changed: 0,
+                context_usage: 0.0,
+                branch: String::new(),
+                is_archived: false,
+                subtitle: first_message.chars().take(100).collect(),
+                storage_size: 0,
+            });
+        }
+    }
+    
+    sessions.sort_by_key(|s| std::cmp::Reverse(s.updated_at_ms));
     sessions
 }
 
+/// 查询 cursorDiskKV 中所有会话相关 key（composerData 及各关联前缀）和值大小的 SQL 与参数
+pub(crate) fn composer_kv_scan_sql() -> (String, Vec<String>) {
+    let mut params = Vec::new();
+    let mut conditions = vec![key_prefix_condition("composerData:", &mut params)];
+    for prefix in COMPOSER_KV_PREFIXES {
+        conditions.push(key_prefix_condition(&format!("{}:", prefix), &mut params));
+    }
+    let sql = format!(
+        "SELECT key, LENGTH(CAST(value AS BLOB)) FROM cursorDiskKV WHERE {}",
+        conditions.join(" OR ")
+    );
+    (sql, params)
+}
+
+/// 按会话 ID 汇总 cursorDiskKV 中关联数据的字节数，key 形如 `composerData:<id>`、`<前缀>:<id>:...`
+fn load_chat_storage_sizes(conn: &Connection) -> HashMap<String, u64> {
+    let mut sizes: HashMap<String, u64> = HashMap::new();
+    if !has_cursor_disk_kv(conn, "main").unwrap_or(false) {
+        return sizes;
+    }
+    
+    let (sql, params) = composer_kv_scan_sql();
+    let Ok(mut stmt) = conn.prepare(&sql) else {
+        return sizes;
+    };
+    let Ok(rows) = stmt.query_map(rusqlite::params_from_iter(params.iter()), |row| Ok((row.get::<_, String>(0)?, row.get::<_, Option<i64>>(1)?))) else {
+        return sizes;
+    };
+    
+    for (key, size) in rows.filter_map(|r| r.ok()) {
+        if let Some(id) = key.split(':').nth(1).filter(|id| !id.is_empty()) {
+            *sizes.entry(id.to_string()).or_insert(0) += size.unwrap_or(0) as u64;
+        }
+    }
+    sizes
+}
+
+/// 全局库中各会话占用的字节数
+fn load_global_chat_storage_sizes() -> Result<HashMap<String, u64>, AppError> {
+    let db_path = get_global_db_path()?;
+    if !db_path.exists() {
+        return Ok(HashMap::new());
+    }
+    Ok(load_chat_storage_sizes(&Connection::open(&db_path)?))
+}
+
+fn add_storage_sizes(sessions: &mut [ChatSession], sizes: &HashMap<String, u64>) {
+    for session in sessions {
+        session.storage_size += sizes.get(&session.id).copied().unwrap_or(0);
+    }
+}
+
+/// 读取工作区数据库中的会话列表，会话所在的键由当前编辑器配置决定。
+/// storage_size 只包含工作区库中的数据，全局库的部分由调用方用 `add_storage_sizes` 补上
+fn load_workspace_sessions(db_path: &Path) -> Vec<ChatSession> {
+    load_sessions_with_keys(db_path, editor::active().chat_keys).unwrap_or_default()
+}
+
+/// 同 `load_workspace_sessions`，键由调用方传入，供不在当前线程上的扫描使用。
+/// 数据库打不开或被占用时返回错误，以便和“没有会话”区分开
+fn load_sessions_with_keys(db_path: &Path, chat_keys: &[&str]) -> Result<Vec<ChatSession>, AppError> {
+    let conn = Connection::open(db_path)?;
+    
+    let mut sessions = Vec::new();
+    for key in chat_keys {
+        let value = match conn.query_row("SELECT value FROM ItemTable WHERE key = ?1", [key], |row| {
+            row.get::<_, String>(0)
+        }) {
+            Ok(value) => value,
+            Err(rusqlite::Error::QueryReturnedNoRows) => continue,
+            Err(e) => return Err(e.into()),
+        };
+        if *key == editor::COMPOSER_DATA_KEY {
+            sessions.extend(parse_composer_data(&value));
+        } else {
+            sessions.extend(parse_interactive_sessions(&value));
+        }
+    }
+    add_storage_sizes(&mut sessions, &load_chat_storage_sizes(&conn));
+    Ok(sessions)
+}
+
 fn get_workspace_projects(ws_json_path: &PathBuf) -> Vec<String> {
     if let Ok(content) = fs::read_to_string(ws_json_path) {
         if let Ok(data) = serde_json::from_str::<serde_json::Value>(&content) {
             if let Some(folders) = data.get("folders").and_then(|v| v.as_array()) {
                 return folders
                     .iter()
-                    .filter_map(|f| {
-                        f.get("path")
-                            .or(f.get("uri"))
-                            .and_then(|v| v.as_str())
-                            .map(|s| s.replace("file://", "").replace("%20", " "))
-                    })
+                    .filter_map(|f| workspace_uri::folder_entry_to_path(f, ws_json_path))
                     .collect();
             }
         }
@@ -349,198 +517,110 @@ fn get_workspace_projects(ws_json_path: &PathBuf) -> Vec<String> {
     Vec::new()
 }
 
-#[tauri::command]
-fn get_all_projects() -> Result<Vec<ProjectStats>, String> {
-    let workspace_storage = get_cursor_user_path().join("workspaceStorage");
-    
+/// 从扫描快照汇总项目：只统计单项目工作区，指向同一文件夹的多个工作区合并
+fn projects_from_scan(snapshot: &ScanSnapshot) -> Vec<ProjectStats> {
     let mut projects: HashMap<String, ProjectStats> = HashMap::new();
     
-    // 遍历所有 workspaceStorage
-    if let Ok(entries) = fs::read_dir(&workspace_storage) {
-        for entry in entries.filter_map(|e| e.ok()) {
-            let ws_path = entry.path();
-            if !ws_path.is_dir() {
-                continue;
-            }
-            
-            let ws_json = ws_path.join("workspace.json");
-            let db_path = ws_path.join("state.vscdb");
-            
-            // 获取项目路径（只处理单项目，跳过工作区）
-            let mut project_path = String::new();
-            if let Ok(content) = fs::read_to_string(&ws_json) {
-                if let Ok(data) = serde_json::from_str::<serde_json::Value>(&content) {
-                    if let Some(folder) = data.get("folder").and_then(|v| v.as_str()) {
-                        project_path = folder.replace("file://", "").replace("%20", " ");
-                    }
-                    // 跳过多项目工作区，它们会在工作区 Tab 中显示
-                }
-            }
-            
-            if project_path.is_empty() {
-                continue;
-            }
-            
-            // 从数据库获取会话
-            if db_path.exists() {
-                if let Ok(conn) = Connection::open(&db_path) {
-                    if let Ok(mut stmt) = conn.prepare("SELECT value FROM ItemTable WHERE key = 'composer.composerData'") {
-                        if let Ok(mut rows) = stmt.query([]) {
-                            if let Ok(Some(row)) = rows.next() {
-                                if let Ok(value) = row.get::<_, String>(0) {
-                                    let sessions = parse_composer_data(&value);
-                                    
-                                    let lines_added: i64 = sessions.iter().map(|s| s.lines_added).sum();
-                                    let lines_removed: i64 = sessions.iter().map(|s| s.lines_removed).sum();
-                                    let files_changed: i64 = sessions.iter().map(|s| s.files_changed).sum();
-                                    
-                                    let project_name = project_path
-                                        .split('/')
-                                        .last()
-                                        .unwrap_or(&project_path)
-                                        .to_string();
-                                    
-                                    let entry = projects.entry(project_path.clone()).or_insert(ProjectStats {
-                                        name: project_name,
-                                        path: project_path.clone(),
-                                        chat_count: 0,
-                                        lines_added: 0,
-                                        lines_removed: 0,
-                                        files_changed: 0,
-                                        chats: Vec::new(),
-                                    });
-                                    
-                                    entry.chat_count += sessions.len() as i64;
-                                    entry.lines_added += lines_added;
-                                    entry.lines_removed += lines_removed;
-                                    entry.files_changed += files_changed;
-                                    entry.chats.extend(sessions);
-                                }
-                            }
-                        }
-                    }
-                }
-            }
-        }
+    for ws in &snapshot.workspaces {
+        // 跳过多项目工作区，它们会在工作区 Tab 中显示
+        let Some(project_path) = ws.folder.as_ref().filter(|p| !p.is_empty()) else {
+            continue;
+        };
+        let sessions = snapshot.sessions(ws);
+        
+        let entry = projects.entry(workspace_uri::path_key(project_path)).or_insert(ProjectStats {
+            name: workspace_uri::path_name(project_path),
+            path: project_path.clone(),
+            location: ws.location.clone(),
+            chat_count: 0,
+            lines_added: 0,
+            lines_removed: 0,
+            files_changed: 0,
+            storage_size: 0,
+            modes: ModeBreakdown::new(),
+            chats: Vec::new(),
+        });
+        
+        entry.chat_count += sessions.len() as i64;
+        entry.lines_added += sessions.iter().map(|s| s.lines_added).sum::<i64>();
+        entry.lines_removed += sessions.iter().map(|s| s.lines_removed).sum::<i64>();
+        entry.files_changed += sessions.iter().map(|s| s.files_changed).sum::<i64>();
+        entry.storage_size += sessions.iter().map(|s| s.storage_size).sum::<u64>();
+        chat_mode::merge(&mut entry.modes, &chat_mode::breakdown(&sessions));
+        entry.chats.extend(sessions);
     }
     
     // 只返回有会话的项目
     let mut result: Vec<ProjectStats> = projects.into_values()
         .filter(|p| p.chat_count > 0)
         .collect();
-    result.sort_by(|a, b| b.lines_added.cmp(&a.lines_added));
-    
-    Ok(result)
+    result.sort_by_key(|p| std::cmp::Reverse(p.lines_added));
+    result
 }
 
-#[tauri::command]
-fn get_workspaces() -> Result<Vec<WorkspaceInfo>, String> {
-    let workspace_storage = get_cursor_user_path().join("workspaceStorage");
-    
-    let mut workspaces = Vec::new();
-    
-    if let Ok(entries) = fs::read_dir(&workspace_storage) {
-        for entry in entries.filter_map(|e| e.ok()) {
-            let ws_path = entry.path();
-            if !ws_path.is_dir() {
-                continue;
-            }
-            
-            let ws_id = ws_path.file_name().unwrap().to_string_lossy().to_string();
-            let ws_json = ws_path.join("workspace.json");
-            let db_path = ws_path.join("state.vscdb");
-            
-            let mut info = WorkspaceInfo {
-                id: ws_id.clone(),
-                created_at: String::new(),
-                projects: Vec::new(),
-                chat_count: 0,
-                lines_added: 0,
-                lines_removed: 0,
-                files_changed: 0,
-                recent_chats: Vec::new(),
-                is_multi_project: false,
-            };
-            
-            // 解析 workspace.json
-            if let Ok(content) = fs::read_to_string(&ws_json) {
-                if let Ok(data) = serde_json::from_str::<serde_json::Value>(&content) {
-                    if let Some(folder) = data.get("folder").and_then(|v| v.as_str()) {
-                        info.projects.push(folder.replace("file://", "").replace("%20", " "));
-                    } else if let Some(workspace) = data.get("workspace").and_then(|v| v.as_str()) {
-                        info.is_multi_project = true;
-                        let ws_file = workspace.replace("file://", "").replace("%20", " ");
-                        let ws_file_path = PathBuf::from(&ws_file);
-                        
-                        // 提取时间戳
-                        if let Some(ts_str) = ws_file.split('/').find(|s| s.chars().all(|c| c.is_numeric()) && s.len() > 10) {
-                            if let Ok(ts) = ts_str.parse::<i64>() {
-                                info.created_at = timestamp_to_string(ts).unwrap_or_default();
-                            }
-                        }
-                        
-                        info.projects = get_workspace_projects(&ws_file_path);
-                    }
-                }
+fn workspaces_from_scan(snapshot: &ScanSnapshot) -> Vec<WorkspaceInfo> {
+    let mut workspaces: Vec<WorkspaceInfo> = snapshot
+        .workspaces
+        .iter()
+        // 只返回有会话的工作区
+        .filter(|ws| !ws.sessions.is_empty())
+        .map(|ws| {
+            let sessions = snapshot.sessions(ws);
+            WorkspaceInfo {
+                id: ws.id.clone(),
+                created_at: ws.created_at.clone(),
+                projects: ws.projects.clone(),
+                chat_count: sessions.len() as i64,
+                lines_added: sessions.iter().map(|s| s.lines_added).sum(),
+                lines_removed: sessions.iter().map(|s| s.lines_removed).sum(),
+                files_changed: sessions.iter().map(|s| s.files_changed).sum(),
+                storage_size: sessions.iter().map(|s| s.storage_size).sum(),
+                // 返回所有会话，由前端进行分页和过滤
+                recent_chats: sessions,
+                is_multi_project: ws.workspace_file.is_some(),
+                location: ws.location.clone(),
             }
-            
-            // 从数据库获取统计
-            if db_path.exists() {
-                if let Ok(conn) = Connection::open(&db_path) {
-                    if let Ok(mut stmt) = conn.prepare("SELECT value FROM ItemTable WHERE key = 'composer.composerData'") {
-                        if let Ok(mut rows) = stmt.query([]) {
-                            if let Ok(Some(row)) = rows.next() {
-                                if let Ok(value) = row.get::<_, String>(0) {
-                                    let sessions = parse_composer_data(&value);
-                                    
-                                    info.chat_count = sessions.len() as i64;
-                                    info.lines_added = sessions.iter().map(|s| s.lines_added).sum();
-                                    info.lines_removed = sessions.iter().map(|s| s.lines_removed).sum();
-                                    info.files_changed = sessions.iter().map(|s| s.files_changed).sum();
-                                    // 返回所有会话，由前端进行分页和过滤
-                                    info.recent_chats = sessions;
-                                }
-                            }
-                        }
-                    }
-                }
-            }
-            
-            // 只返回有会话的工作区
-            if info.chat_count > 0 {
-                workspaces.push(info);
-            }
-        }
-    }
+        })
+        .collect();
     
-    workspaces.sort_by(|a, b| b.lines_added.cmp(&a.lines_added));
-    Ok(workspaces)
+    workspaces.sort_by_key(|w| std::cmp::Reverse(w.lines_added));
+    workspaces
 }
 
-#[tauri::command]
-fn get_overview() -> Result<OverviewStats, String> {
-    let projects = get_all_projects()?;
-    
+fn overview_from_projects(projects: &[ProjectStats]) -> OverviewStats {
     let total_projects = projects.len() as i64;
     let total_chats: i64 = projects.iter().map(|p| p.chat_count).sum();
     let total_lines_added: i64 = projects.iter().map(|p| p.lines_added).sum();
     let total_lines_removed: i64 = projects.iter().map(|p| p.lines_removed).sum();
     let total_files_changed: i64 = projects.iter().map(|p| p.files_changed).sum();
     
-    let mut agent_count = 0i64;
-    let mut chat_count = 0i64;
-    
-    for p in &projects {
-        for c in &p.chats {
-            if c.mode == "agent" {
-                agent_count += 1;
-            } else {
-                chat_count += 1;
-            }
-        }
+    let mut modes = ModeBreakdown::new();
+    let mut locations: HashMap<WorkspaceLocation, LocationUsage> = HashMap::new();
+    
+    for p in projects {
+        let usage = locations.entry(p.location.clone()).or_insert_with(|| LocationUsage {
+            location: p.location.clone(),
+            label: p.location.label(),
+            project_count: 0,
+            chat_count: 0,
+            lines_added: 0,
+            lines_removed: 0,
+        });
+        usage.project_count += 1;
+        usage.chat_count += p.chat_count;
+        usage.lines_added += p.lines_added;
+        usage.lines_removed += p.lines_removed;
+        
+        chat_mode::merge(&mut modes, &p.modes);
     }
     
-    Ok(OverviewStats {
+    let agent_count = modes.get(&chat_mode::ChatMode::Agent).map_or(0, |m| m.chat_count);
+    
+    // 本地在前，远程按聊天数量排序
+    let mut locations: Vec<LocationUsage> = locations.into_values().collect();
+    locations.sort_by_key(|l| (l.location != WorkspaceLocation::Local, std::cmp::Reverse(l.chat_count)));
+    
+    OverviewStats {
         total_projects,
         total_chats,
         total_lines_added,
@@ -548,17 +628,40 @@ fn get_overview() -> Result<OverviewStats, String> {
         net_lines: total_lines_added - total_lines_removed,
         total_files_changed,
         agent_mode_count: agent_count,
-        chat_mode_count: chat_count,
-    })
+        chat_mode_count: total_chats - agent_count,
+        modes,
+        locations,
+    }
+}
+
+#[cfg_attr(feature = "gui", tauri::command)]
+pub fn get_all_projects() -> Result<Vec<ProjectStats>, String> {
+    Ok(projects_from_scan(&workspace_scan::scan()?))
 }
 
-#[tauri::command]
-fn get_full_analysis() -> Result<AnalysisResult, String> {
+#[cfg_attr(feature = "gui", tauri::command)]
+pub fn get_workspaces() -> Result<Vec<WorkspaceInfo>, String> {
+    Ok(workspaces_from_scan(&workspace_scan::scan()?))
+}
+
+#[cfg_attr(feature = "gui", tauri::command)]
+pub fn get_overview() -> Result<OverviewStats, String> {
+    Ok(overview_from_projects(&get_all_projects()?))
+}
+
+/// 工作区只扫描一次，项目、工作区和概览都从同一份快照得出
+#[cfg_attr(feature = "gui", tauri::command)]
+pub fn get_full_analysis() -> Result<AnalysisResult, String> {
     let storage = get_storage_info()?;
-    let overview = get_overview()?;
     let database = get_database_stats()?;
-    let projects = get_all_projects()?;
-    let workspaces = get_workspaces()?;
+    let snapshot = workspace_scan::scan()?;
+    let projects = projects_from_scan(&snapshot);
+    let overview = overview_from_projects(&projects);
+    let workspaces = workspaces_from_scan(&snapshot);
+    // 记录失败不影响分析结果，原因随结果返回
+    let usage_error = usage_history::record(&storage, &workspaces, &snapshot.unreadable_workspaces())
+        .err()
+        .map(|e| e.to_string());
     
     Ok(AnalysisResult {
         storage,
@@ -566,13 +669,14 @@ fn get_full_analysis() -> Result<AnalysisResult, String> {
         database,
         projects,
         workspaces,
+        usage_error,
     })
 }
 
 // ==================== 版本信息 ====================
 
-#[tauri::command]
-fn get_app_version() -> String {
+#[cfg_attr(feature = "gui", tauri::command)]
+pub fn get_app_version() -> String {
     APP_VERSION.to_string()
 }
 
@@ -588,17 +692,49 @@ pub struct TrashItem {
     pub lines_added: i64,
     pub lines_removed: i64,
     pub files_changed: i64,
+    /// 按设置的时区格式化的删除时间
     pub deleted_at: String,
+    pub deleted_at_ms: i64,
     pub original_data: String,
+    pub workspace_id: String,
+    pub kv_count: i64,
+    pub kv_size: u64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct RestoreFailure {
+    pub trash_id: i64,
+    pub chat_name: String,
+    pub error: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct RestoreResult {
+    pub restored_count: i64,
+    pub failures: Vec<RestoreFailure>,
 }
 
 /// 获取垃圾桶数据库路径（跨平台）
-fn get_trash_db_path() -> PathBuf {
-    get_cursor_user_path().join("cursor-analysis-trash.db")
+fn get_trash_db_path() -> Result<PathBuf, AppError> {
+    Ok(get_cursor_user_path()?.join("cursor-analysis-trash.db"))
+}
+
+/// 给旧版本的 trash 表补上缺少的列，返回是否新增
+fn add_trash_column(conn: &Connection, column: &str, definition: &str) -> Result<bool, String> {
+    let exists = conn
+        .prepare("SELECT 1 FROM pragma_table_info('trash') WHERE name = ?1")
+        .and_then(|mut stmt| stmt.exists([column]))
+        .map_err(|e| e.to_string())?;
+    if exists {
+        return Ok(false);
+    }
+    conn.execute(&format!("ALTER TABLE trash ADD COLUMN {} {}", column, definition), [])
+        .map_err(|e| e.to_string())?;
+    Ok(true)
 }
 
 fn init_trash_db() -> Result<Connection, String> {
-    let db_path = get_trash_db_path();
+    let db_path = get_trash_db_path()?;
     let conn = Connection::open(&db_path).map_err(|e| e.to_string())?;
     
     conn.execute(
@@ -617,50 +753,214 @@ fn init_trash_db() -> Result<Connection, String> {
         [],
     ).map_err(|e| e.to_string())?;
     
+    // 旧版本的垃圾桶没有 workspace_id 列，需要补上
+    add_trash_column(&conn, "workspace_id", "TEXT NOT NULL DEFAULT ''")?;
+    
+    // 旧版本只保存了 UTC 的删除时间文字，补上毫秒时间戳列并从文字换算
+    if add_trash_column(&conn, "deleted_at_ms", "INTEGER")? {
+        conn.execute(
+            "UPDATE trash SET deleted_at_ms = CAST(strftime('%s', deleted_at) AS INTEGER) * 1000 WHERE deleted_at_ms IS NULL",
+            [],
+        ).map_err(|e| e.to_string())?;
+    }
+    
+    // 会话删除前在 composerData 中的位置，旧记录为空时恢复到列表末尾
+    add_trash_column(&conn, "original_index", "INTEGER")?;
+    add_trash_column(&conn, "was_selected", "INTEGER NOT NULL DEFAULT 0")?;
+    add_trash_column(&conn, "was_focused", "INTEGER NOT NULL DEFAULT 0")?;
+    
+    // 会话在 cursorDiskKV 中被删除的原始行，source 为 global / workspace
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS trash_kv (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            trash_id INTEGER NOT NULL,
+            source TEXT NOT NULL,
+            key TEXT NOT NULL,
+            value BLOB
+        )",
+        [],
+    ).map_err(|e| e.to_string())?;
+    conn.execute("CREATE INDEX IF NOT EXISTS idx_trash_kv_trash_id ON trash_kv (trash_id)", [])
+        .map_err(|e| e.to_string())?;
+    
     Ok(conn)
 }
 
-fn add_to_trash(chat: &serde_json::Value, project_path: &str) -> Result<(), String> {
-    let conn = init_trash_db()?;
-    
+/// workspaceStorage 下的目录名是哈希（空窗口为时间戳），只含字母和数字。
+/// 由调用方传入的 ID 要先过这一关，避免 `..` 之类的值把路径拼到 workspaceStorage 之外
+pub(crate) fn is_workspace_id(id: &str) -> bool {
+    !id.is_empty() && id.chars().all(|c| c.is_ascii_alphanumeric())
+}
+
+pub(crate) fn validate_workspace_id(id: &str) -> Result<(), AppError> {
+    if !is_workspace_id(id) {
+        return Err(AppError::InvalidData(format!("无效的工作区 ID: {}", id)));
+    }
+    Ok(())
+}
+
+/// 从 state.vscdb 路径中取出工作区 ID（即 workspaceStorage 下的目录名）
+fn workspace_id_from_db_path(db_path: &Path) -> String {
+    db_path
+        .parent()
+        .and_then(|p| p.file_name())
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default()
+}
+
+/// composerData 中记录打开状态的会话 ID 列表
+const COMPOSER_ID_LISTS: [&str; 2] = ["selectedComposerIds", "lastFocusedComposerIds"];
+
+/// 会话在工作区 composerData 中的位置和打开状态，恢复时按原样放回
+#[derive(Debug, Default, Clone)]
+pub(crate) struct ComposerPlacement {
+    pub index: Option<i64>,
+    pub selected: bool,
+    pub focused: bool,
+}
+
+fn composer_id_list(data: &serde_json::Value, key: &str) -> Vec<String> {
+    data.get(key)
+        .and_then(|v| v.as_array())
+        .map(|ids| ids.iter().filter_map(|v| v.as_str()).map(|s| s.to_string()).collect())
+        .unwrap_or_default()
+}
+
+fn add_to_trash(
+    conn: &Connection,
+    chat: &serde_json::Value,
+    project_path: &str,
+    workspace_id: &str,
+    placement: &ComposerPlacement,
+) -> Result<i64, AppError> {
     let chat_id = chat.get("composerId").and_then(|v| v.as_str()).unwrap_or("");
     let chat_name = chat.get("name").and_then(|v| v.as_str()).unwrap_or("Unnamed");
     let mode = chat.get("unifiedMode").and_then(|v| v.as_str()).unwrap_or("unknown");
     let lines_added = chat.get("totalLinesAdded").and_then(|v| v.as_i64()).unwrap_or(0);
     let lines_removed = chat.get("totalLinesRemoved").and_then(|v| v.as_i64()).unwrap_or(0);
     let files_changed = chat.get("filesChangedCount").and_then(|v| v.as_i64()).unwrap_or(0);
-    let deleted_at = Utc::now().format("%Y-%m-%d %H:%M:%S").to_string();
-    let original_data = serde_json::to_string(chat).unwrap_or_default();
+    let now = Utc::now();
+    let deleted_at = now.format("%Y-%m-%d %H:%M:%S").to_string();
+    let original_data = serde_json::to_string(chat)?;
     
     conn.execute(
-        "INSERT INTO trash (chat_id, chat_name, project_path, mode, lines_added, lines_removed, files_changed, deleted_at, original_data)
-         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
-        [
+        "INSERT INTO trash.trash (chat_id, chat_name, project_path, mode, lines_added, lines_removed, files_changed, deleted_at, deleted_at_ms, original_data, workspace_id, original_index, was_selected, was_focused)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14)",
+        rusqlite::params![
             chat_id,
             chat_name,
             project_path,
             mode,
-            &lines_added.to_string(),
-            &lines_removed.to_string(),
-            &files_changed.to_string(),
-            &deleted_at,
-            &original_data,
+            lines_added.to_string(),
+            lines_removed.to_string(),
+            files_changed.to_string(),
+            deleted_at,
+            now.timestamp_millis(),
+            original_data,
+            workspace_id,
+            placement.index,
+            placement.selected,
+            placement.focused,
         ],
-    ).map_err(|e| e.to_string())?;
+    ).map_err(|e| AppError::Trash(e.to_string()))?;
+    
+    Ok(conn.last_insert_rowid())
+}
+
+/// 会话在 cursorDiskKV 中关联数据的 key 前缀，key 形如 `<前缀>:<composerId>:...`
+const COMPOSER_KV_PREFIXES: &[&str] = &["bubbleId", "checkpointId", "agentKv"];
+
+/// 匹配以 `prefix` 开头的 key 的范围条件 `(key >= ?n AND key < ?m)`，两个边界追加到 `params`。
+/// cursorDiskKV 的 key 有唯一索引，LIKE 用不上它，范围条件可以走索引
+pub(crate) fn key_prefix_condition(prefix: &str, params: &mut Vec<String>) -> String {
+    params.push(prefix.to_string());
+    params.push(prefix_upper_bound(prefix));
+    format!("(key >= ?{} AND key < ?{})", params.len() - 1, params.len())
+}
+
+/// 以 `prefix` 开头的字符串的上界（不含）：最后一个字符加一，如 `bubbleId:<id>:` → `bubbleId:<id>;`
+fn prefix_upper_bound(prefix: &str) -> String {
+    let mut chars: Vec<char> = prefix.chars().collect();
+    while let Some(last) = chars.pop() {
+        let next = match last {
+            '\u{D7FF}' => Some('\u{E000}'),
+            c => char::from_u32(c as u32 + 1),
+        };
+        if let Some(next) = next {
+            chars.push(next);
+            return chars.into_iter().collect();
+        }
+    }
+    // 空前缀或全是最大字符时没有上界，用一个比任何 key 都大的值
+    char::MAX.to_string().repeat(2)
+}
+
+fn has_cursor_disk_kv(conn: &Connection, schema: &str) -> Result<bool, AppError> {
+    let sql = format!("SELECT 1 FROM {}.sqlite_master WHERE type = 'table' AND name = 'cursorDiskKV'", schema);
+    Ok(conn.prepare(&sql)?.exists([])?)
+}
+
+/// 读取会话在 cursorDiskKV 中的全部数据（composerData、bubble、checkpoint、agentKv）
+fn collect_composer_kv(conn: &Connection, schema: &str, chat_id: &str) -> Result<Vec<(String, Value)>, AppError> {
+    if !has_cursor_disk_kv(conn, schema)? {
+        return Ok(Vec::new());
+    }
+    
+    let mut sql = format!("SELECT key, value FROM {}.cursorDiskKV WHERE key = ?1", schema);
+    let mut params = vec![format!("composerData:{}", chat_id)];
+    for prefix in COMPOSER_KV_PREFIXES {
+        let condition = key_prefix_condition(&format!("{}:{}:", prefix, chat_id), &mut params);
+        sql.push_str(&format!(" OR {}", condition));
+    }
+    
+    let mut stmt = conn.prepare(&sql)?;
+    let rows = stmt.query_map(rusqlite::params_from_iter(params.iter()), |row| {
+        Ok((row.get::<_, String>(0)?, row.get::<_, Value>(1)?))
+    })?;
+    
+    Ok(rows.collect::<Result<Vec<_>, _>>()?)
+}
+
+/// 把会话在 schema 库 cursorDiskKV 中的数据移入垃圾桶，再从源数据库删除
+fn move_composer_kv_to_trash(
+    conn: &Connection,
+    schema: &str,
+    source: &str,
+    chat_id: &str,
+    trash_id: i64,
+) -> Result<(), AppError> {
+    let rows = collect_composer_kv(conn, schema, chat_id)?;
+    
+    for (key, value) in &rows {
+        conn.execute(
+            "INSERT INTO trash.trash_kv (trash_id, source, key, value) VALUES (?1, ?2, ?3, ?4)",
+            rusqlite::params![trash_id, source, key, value],
+        ).map_err(|e| AppError::Trash(e.to_string()))?;
+        conn.execute(&format!("DELETE FROM {}.cursorDiskKV WHERE key = ?", schema), [key])?;
+    }
     
     Ok(())
 }
 
-#[tauri::command]
-fn get_trash_items() -> Result<Vec<TrashItem>, String> {
+#[cfg_attr(feature = "gui", tauri::command)]
+pub fn get_trash_items() -> Result<Vec<TrashItem>, String> {
     let conn = init_trash_db()?;
     
     let mut stmt = conn.prepare(
-        "SELECT id, chat_id, chat_name, project_path, mode, lines_added, lines_removed, files_changed, deleted_at, original_data 
-         FROM trash ORDER BY deleted_at DESC"
+        "SELECT t.id, t.chat_id, t.chat_name, t.project_path, t.mode, t.lines_added, t.lines_removed, t.files_changed, t.deleted_at, t.original_data, t.workspace_id,
+                COUNT(k.id), COALESCE(SUM(LENGTH(k.value)), 0), COALESCE(t.deleted_at_ms, 0)
+         FROM trash t LEFT JOIN trash_kv k ON k.trash_id = t.id
+         GROUP BY t.id ORDER BY 14 DESC, t.id DESC"
     ).map_err(|e| e.to_string())?;
     
+    let zone = timezone::current();
     let items = stmt.query_map([], |row| {
+        // 换算不出时间戳的旧记录直接显示保存的文字
+        let deleted_at_ms: i64 = row.get(13)?;
+        let deleted_at = match zone.format(deleted_at_ms, "%Y-%m-%d %H:%M:%S") {
+            Some(formatted) if deleted_at_ms > 0 => formatted,
+            _ => row.get(8)?,
+        };
         Ok(TrashItem {
             id: row.get(0)?,
             chat_id: row.get(1)?,
@@ -670,33 +970,35 @@ fn get_trash_items() -> Result<Vec<TrashItem>, String> {
             lines_added: row.get(5)?,
             lines_removed: row.get(6)?,
             files_changed: row.get(7)?,
-            deleted_at: row.get(8)?,
+            deleted_at,
+            deleted_at_ms,
             original_data: row.get(9)?,
+            workspace_id: row.get(10)?,
+            kv_count: row.get(11)?,
+            kv_size: row.get::<_, i64>(12)? as u64,
         })
     }).map_err(|e| e.to_string())?;
     
-    let mut result = Vec::new();
-    for item in items {
-        if let Ok(i) = item {
-            result.push(i);
-        }
-    }
+    let result: Vec<TrashItem> = items.flatten().collect();
     
     Ok(result)
 }
 
-#[tauri::command]
-fn clear_trash() -> Result<i64, String> {
+#[cfg_attr(feature = "gui", tauri::command)]
+pub fn clear_trash() -> Result<i64, String> {
     let conn = init_trash_db()?;
     let count: i64 = conn.query_row("SELECT COUNT(*) FROM trash", [], |row| row.get(0))
         .map_err(|e| e.to_string())?;
+    conn.execute("DELETE FROM trash_kv", []).map_err(|e| e.to_string())?;
     conn.execute("DELETE FROM trash", []).map_err(|e| e.to_string())?;
     Ok(count)
 }
 
-#[tauri::command]
-fn delete_trash_item(trash_id: i64) -> Result<bool, String> {
+#[cfg_attr(feature = "gui", tauri::command)]
+pub fn delete_trash_item(trash_id: i64) -> Result<bool, String> {
     let conn = init_trash_db()?;
+    conn.execute("DELETE FROM trash_kv WHERE trash_id = ?", [trash_id])
+        .map_err(|e| e.to_string())?;
     let affected = conn.execute("DELETE FROM trash WHERE id = ?", [trash_id])
         .map_err(|e| e.to_string())?;
     Ok(affected > 0)
@@ -704,8 +1006,138 @@ fn delete_trash_item(trash_id: i64) -> Result<bool, String> {
 
 // ==================== 删除功能 ====================
 
-fn find_workspace_db_by_project(project_path: &str) -> Option<PathBuf> {
-    let workspace_storage = get_cursor_user_path().join("workspaceStorage");
+/// 删除范围
+enum DeleteTarget<'a> {
+    /// 指定 ID 的会话
+    Chats(&'a [String]),
+    /// 工作区内全部会话
+    All,
+}
+
+/// 删除和恢复只支持 Cursor 的 composerData 结构
+fn ensure_cleanup_supported() -> Result<(), AppError> {
+    let profile = editor::active();
+    if profile.supports_cleanup() {
+        Ok(())
+    } else {
+        Err(AppError::InvalidData(format!("{} 的会话数据暂不支持删除和恢复", profile.name)))
+    }
+}
+
+/// 在一个事务中删除工作区中的会话：写入垃圾桶、更新 composerData、清理 cursorDiskKV。
+/// 垃圾桶库和全局库通过 ATTACH 挂到同一连接上，任一步失败都会整体回滚。
+fn delete_composers(
+    db_path: &Path,
+    project_path: &str,
+    workspace_id: &str,
+    target: DeleteTarget,
+) -> Result<i64, AppError> {
+    ensure_cleanup_supported()?;
+    
+    // Cursor 运行时写入可能被覆盖，直接拒绝
+    let global_db = get_global_db_path()?;
+    cursor_guard::ensure_writable(db_path)?;
+    cursor_guard::ensure_writable(&global_db)?;
+    
+    // 修改前先做快照
+    snapshot::take_snapshot(db_path, "删除会话之前")?;
+    snapshot::take_snapshot(&global_db, "删除会话之前")?;
+    
+    // 确保垃圾桶表结构存在
+    init_trash_db().map_err(AppError::Trash)?;
+    
+    let mut conn = Connection::open(db_path)?;
+    conn.execute("ATTACH DATABASE ?1 AS trash", [get_trash_db_path()?.to_string_lossy()])?;
+    
+    let has_global = global_db.exists();
+    if has_global {
+        conn.execute("ATTACH DATABASE ?1 AS global", [global_db.to_string_lossy()])?;
+    }
+    
+    let tx = conn.transaction()?;
+    
+    // 读取现有的 composerData
+    let value: String = tx
+        .query_row("SELECT value FROM main.ItemTable WHERE key = 'composer.composerData'", [], |row| row.get(0))
+        .map_err(|e| match e {
+            rusqlite::Error::QueryReturnedNoRows => AppError::NotFound("工作区中没有会话数据".to_string()),
+            e => e.into(),
+        })?;
+    
+    let mut data: serde_json::Value = serde_json::from_str(&value)?;
+    
+    // 先把要删除的会话保存到垃圾桶，连同它在列表中的位置和打开状态
+    let selected_ids = composer_id_list(&data, COMPOSER_ID_LISTS[0]);
+    let focused_ids = composer_id_list(&data, COMPOSER_ID_LISTS[1]);
+    let mut deleted: Vec<(String, i64)> = Vec::new();
+    if let Some(composers) = data.get_mut("allComposers").and_then(|v| v.as_array_mut()) {
+        for (index, c) in composers.iter().enumerate() {
+            let Some(id) = c.get("composerId").and_then(|v| v.as_str()) else {
+                continue;
+            };
+            let selected = match target {
+                DeleteTarget::Chats(ids) => ids.iter().any(|i| i == id),
+                DeleteTarget::All => c.get("type").and_then(|v| v.as_str()) == Some("head"),
+            };
+            if selected {
+                let placement = ComposerPlacement {
+                    index: Some(index as i64),
+                    selected: selected_ids.iter().any(|s| s == id),
+                    focused: focused_ids.iter().any(|s| s == id),
+                };
+                let trash_id = add_to_trash(&tx, c, project_path, workspace_id, &placement)?;
+                deleted.push((id.to_string(), trash_id));
+            }
+        }
+        
+        // 只移除已经写入垃圾桶的会话，其余条目（如子会话）原样保留，保证删掉的都能恢复
+        composers.retain(|c| {
+            c.get("composerId")
+                .and_then(|v| v.as_str())
+                .map(|id| !deleted.iter().any(|(d, _)| d == id))
+                .unwrap_or(true)
+        });
+    }
+    
+    if deleted.is_empty() {
+        return match target {
+            DeleteTarget::Chats(_) => Err(AppError::NotFound("未找到指定的会话".to_string())),
+            DeleteTarget::All => Ok(0),
+        };
+    }
+    
+    // 已删除的会话不再保留打开状态
+    for key in COMPOSER_ID_LISTS {
+        if let Some(ids) = data.get_mut(key).and_then(|v| v.as_array_mut()) {
+            ids.retain(|v| v.as_str().is_none_or(|id| !deleted.iter().any(|(d, _)| d == id)));
+        }
+    }
+    
+    // 写回数据库
+    let new_value = serde_json::to_string(&data)?;
+    tx.execute(
+        "UPDATE main.ItemTable SET value = ? WHERE key = 'composer.composerData'",
+        [&new_value],
+    )?;
+    
+    // 把相关的 bubble、checkpoint 等数据移入垃圾桶
+    for (chat_id, trash_id) in &deleted {
+        move_composer_kv_to_trash(&tx, "main", "workspace", chat_id, *trash_id)?;
+        if has_global {
+            move_composer_kv_to_trash(&tx, "global", "global", chat_id, *trash_id)?;
+        }
+    }
+    
+    tx.commit()?;
+    
+    Ok(deleted.len() as i64)
+}
+
+/// 找出项目对应的所有工作区数据库。同一文件夹可能对应多个 workspaceStorage 目录
+/// （如文件夹被移动后又移回），按最近修改时间降序返回
+fn find_workspace_dbs_by_project(project_path: &str) -> Result<Vec<PathBuf>, AppError> {
+    let workspace_storage = get_cursor_user_path()?.join("workspaceStorage");
+    let mut found: Vec<(Option<std::time::SystemTime>, PathBuf)> = Vec::new();
     
     if let Ok(entries) = fs::read_dir(&workspace_storage) {
         for entry in entries.filter_map(|e| e.ok()) {
@@ -719,23 +1151,28 @@ fn find_workspace_db_by_project(project_path: &str) -> Option<PathBuf> {
                 if let Ok(data) = serde_json::from_str::<serde_json::Value>(&content) {
                     // 检查单项目工作区
                     if let Some(folder) = data.get("folder").and_then(|v| v.as_str()) {
-                        let folder_path = folder.replace("file://", "").replace("%20", " ");
-                        if folder_path == project_path {
+                        if workspace_uri::same_path(&workspace_uri::uri_to_path(folder), project_path) {
                             let db_path = ws_path.join("state.vscdb");
-                            if db_path.exists() {
-                                return Some(db_path);
+                            if let Ok(meta) = fs::metadata(&db_path) {
+                                found.push((meta.modified().ok(), db_path));
                             }
+                            continue;
                         }
                     }
                     // 检查多项目工作区
                     if let Some(workspace) = data.get("workspace").and_then(|v| v.as_str()) {
-                        let ws_file = workspace.replace("file://", "").replace("%20", " ");
-                        let ws_file_path = PathBuf::from(&ws_file);
+                        let ws_file_path = PathBuf::from(workspace_uri::uri_to_path(workspace));
                         let projects = get_workspace_projects(&ws_file_path);
-                        if projects.iter().any(|p| p == project_path) || project_path.contains(" + ") {
+                        // 多项目工作区整体作为项目时，路径是各项目用 " + " 连接的列表
+                        let matched = if project_path.contains(workspace_uri::PROJECT_LIST_SEPARATOR) {
+                            workspace_uri::same_project_list(project_path, &projects)
+                        } else {
+                            projects.iter().any(|p| workspace_uri::same_path(p, project_path))
+                        };
+                        if matched {
                             let db_path = ws_path.join("state.vscdb");
-                            if db_path.exists() {
-                                return Some(db_path);
+                            if let Ok(meta) = fs::metadata(&db_path) {
+                                found.push((meta.modified().ok(), db_path));
                             }
                         }
                     }
@@ -743,208 +1180,81 @@ fn find_workspace_db_by_project(project_path: &str) -> Option<PathBuf> {
             }
         }
     }
-    None
+    
+    found.sort_by_key(|(modified, _)| std::cmp::Reverse(*modified));
+    Ok(found.into_iter().map(|(_, path)| path).collect())
 }
 
-#[tauri::command]
-fn delete_chat(project_path: String, chat_id: String) -> Result<bool, String> {
-    // 找到对应的工作区数据库
-    let db_path = find_workspace_db_by_project(&project_path)
-        .ok_or_else(|| format!("找不到项目 {} 的数据库", project_path))?;
-    
-    let conn = Connection::open(&db_path).map_err(|e| e.to_string())?;
-    
-    // 读取现有的 composerData
-    let mut stmt = conn.prepare("SELECT value FROM ItemTable WHERE key = 'composer.composerData'")
-        .map_err(|e| e.to_string())?;
-    
-    let value: String = stmt.query_row([], |row| row.get(0))
-        .map_err(|e| e.to_string())?;
-    
-    // 解析 JSON
-    let mut data: serde_json::Value = serde_json::from_str(&value)
-        .map_err(|e| e.to_string())?;
-    
-    // 找到并保存到垃圾桶，然后删除
-    let mut found = false;
-    if let Some(composers) = data.get_mut("allComposers").and_then(|v| v.as_array_mut()) {
-        // 先找到要删除的会话并保存到垃圾桶
-        for c in composers.iter() {
-            if c.get("composerId").and_then(|v| v.as_str()) == Some(&chat_id) {
-                let _ = add_to_trash(c, &project_path);
-                found = true;
-                break;
-            }
+/// 项目的工作区数据库，一个都没有时报错
+fn project_workspace_dbs(project_path: &str) -> Result<Vec<PathBuf>, AppError> {
+    let dbs = find_workspace_dbs_by_project(project_path)?;
+    if dbs.is_empty() {
+        return Err(AppError::NotFound(format!("找不到项目 {} 的数据库", project_path)));
+    }
+    Ok(dbs)
+}
+
+/// 按会话实际所在的工作区数据库分别删除
+fn delete_project_composers(project_path: &str, chat_ids: &[String]) -> Result<i64, AppError> {
+    let mut remaining: Vec<String> = chat_ids.to_vec();
+    let mut deleted = 0;
+    
+    for db_path in project_workspace_dbs(project_path)? {
+        if remaining.is_empty() {
+            break;
         }
-        
-        if !found {
-            return Err("未找到指定的会话".to_string());
+        let mut known = HashSet::new();
+        kv_orphans::composer_ids(&Connection::open(&db_path)?, &mut known)?;
+        let (here, rest): (Vec<String>, Vec<String>) = remaining.into_iter().partition(|id| known.contains(id));
+        remaining = rest;
+        if here.is_empty() {
+            continue;
         }
         
-        // 过滤掉要删除的会话
-        composers.retain(|c| {
-            c.get("composerId").and_then(|v| v.as_str()) != Some(&chat_id)
-        });
+        let workspace_id = workspace_id_from_db_path(&db_path);
+        deleted += delete_composers(&db_path, project_path, &workspace_id, DeleteTarget::Chats(&here))?;
     }
     
-    // 写回数据库
-    let new_value = serde_json::to_string(&data).map_err(|e| e.to_string())?;
-    conn.execute(
-        "UPDATE ItemTable SET value = ? WHERE key = 'composer.composerData'",
-        [&new_value],
-    ).map_err(|e| e.to_string())?;
-    
-    // 同时删除相关的 bubble 和 checkpoint 数据
-    let _ = conn.execute(
-        "DELETE FROM cursorDiskKV WHERE key LIKE ?",
-        [format!("bubbleId:{}:%", chat_id)],
-    );
-    let _ = conn.execute(
-        "DELETE FROM cursorDiskKV WHERE key LIKE ?",
-        [format!("checkpointId:{}:%", chat_id)],
-    );
-    
+    if deleted == 0 {
+        return Err(AppError::NotFound("未找到指定的会话".to_string()));
+    }
+    Ok(deleted)
+}
+
+#[cfg_attr(feature = "gui", tauri::command)]
+pub fn delete_chat(project_path: String, chat_id: String) -> Result<bool, AppError> {
+    delete_project_composers(&project_path, &[chat_id])?;
     Ok(true)
 }
 
-#[tauri::command]
-fn delete_chats_batch(project_path: String, chat_ids: Vec<String>) -> Result<i64, String> {
+#[cfg_attr(feature = "gui", tauri::command)]
+pub fn delete_chats_batch(project_path: String, chat_ids: Vec<String>) -> Result<i64, AppError> {
     if chat_ids.is_empty() {
         return Ok(0);
     }
-    
-    // 找到对应的工作区数据库
-    let db_path = find_workspace_db_by_project(&project_path)
-        .ok_or_else(|| format!("找不到项目 {} 的数据库", project_path))?;
-    
-    let conn = Connection::open(&db_path).map_err(|e| e.to_string())?;
-    
-    // 读取现有的 composerData
-    let mut stmt = conn.prepare("SELECT value FROM ItemTable WHERE key = 'composer.composerData'")
-        .map_err(|e| e.to_string())?;
-    
-    let value: String = stmt.query_row([], |row| row.get(0))
-        .map_err(|e| e.to_string())?;
-    
-    // 解析 JSON
-    let mut data: serde_json::Value = serde_json::from_str(&value)
-        .map_err(|e| e.to_string())?;
-    
-    // 先保存到垃圾桶，然后删除
-    let mut deleted_count = 0i64;
-    if let Some(composers) = data.get_mut("allComposers").and_then(|v| v.as_array_mut()) {
-        // 先保存到垃圾桶
-        for c in composers.iter() {
-            if let Some(id) = c.get("composerId").and_then(|v| v.as_str()) {
-                if chat_ids.contains(&id.to_string()) {
-                    let _ = add_to_trash(c, &project_path);
-                }
-            }
-        }
-        
-        // 过滤掉要删除的会话
-        let original_len = composers.len();
-        composers.retain(|c| {
-            if let Some(id) = c.get("composerId").and_then(|v| v.as_str()) {
-                !chat_ids.contains(&id.to_string())
-            } else {
-                true
-            }
-        });
-        deleted_count = (original_len - composers.len()) as i64;
-    }
-    
-    // 写回数据库
-    let new_value = serde_json::to_string(&data).map_err(|e| e.to_string())?;
-    conn.execute(
-        "UPDATE ItemTable SET value = ? WHERE key = 'composer.composerData'",
-        [&new_value],
-    ).map_err(|e| e.to_string())?;
-    
-    // 删除相关的 bubble 和 checkpoint 数据
-    for id in &chat_ids {
-        let _ = conn.execute(
-            "DELETE FROM cursorDiskKV WHERE key LIKE ?",
-            [format!("bubbleId:{}:%", id)],
-        );
-        let _ = conn.execute(
-            "DELETE FROM cursorDiskKV WHERE key LIKE ?",
-            [format!("checkpointId:{}:%", id)],
-        );
-    }
-    
-    Ok(deleted_count)
+    delete_project_composers(&project_path, &chat_ids)
 }
 
-#[tauri::command]
-fn delete_project_chats(project_path: String) -> Result<i64, String> {
-    // 找到对应的工作区数据库
-    let db_path = find_workspace_db_by_project(&project_path)
-        .ok_or_else(|| format!("找不到项目 {} 的数据库", project_path))?;
-    
-    let conn = Connection::open(&db_path).map_err(|e| e.to_string())?;
-    
-    // 读取现有的 composerData
-    let mut stmt = conn.prepare("SELECT value FROM ItemTable WHERE key = 'composer.composerData'")
-        .map_err(|e| e.to_string())?;
-    
-    let value: String = stmt.query_row([], |row| row.get(0))
-        .map_err(|e| e.to_string())?;
-    
-    // 解析 JSON
-    let mut data: serde_json::Value = serde_json::from_str(&value)
-        .map_err(|e| e.to_string())?;
-    
-    // 收集所有要删除的会话 ID 并保存到垃圾桶
-    let mut deleted_ids: Vec<String> = Vec::new();
-    
-    if let Some(composers) = data.get_mut("allComposers").and_then(|v| v.as_array_mut()) {
-        for c in composers.iter() {
-            if c.get("type").and_then(|v| v.as_str()) == Some("head") {
-                if let Some(id) = c.get("composerId").and_then(|v| v.as_str()) {
-                    // 保存到垃圾桶
-                    let _ = add_to_trash(c, &project_path);
-                    deleted_ids.push(id.to_string());
-                }
-            }
-        }
-        
-        // 清空所有会话
-        composers.clear();
-    }
-    
-    let deleted_count = deleted_ids.len() as i64;
-    
-    // 写回数据库
-    let new_value = serde_json::to_string(&data).map_err(|e| e.to_string())?;
-    conn.execute(
-        "UPDATE ItemTable SET value = ? WHERE key = 'composer.composerData'",
-        [&new_value],
-    ).map_err(|e| e.to_string())?;
-    
-    // 删除相关的 bubble 和 checkpoint 数据
-    for id in &deleted_ids {
-        let _ = conn.execute(
-            "DELETE FROM cursorDiskKV WHERE key LIKE ?",
-            [format!("bubbleId:{}:%", id)],
-        );
-        let _ = conn.execute(
-            "DELETE FROM cursorDiskKV WHERE key LIKE ?",
-            [format!("checkpointId:{}:%", id)],
-        );
+/// 清空项目对应的所有工作区数据库
+#[cfg_attr(feature = "gui", tauri::command)]
+pub fn delete_project_chats(project_path: String) -> Result<i64, AppError> {
+    let mut deleted = 0;
+    for db_path in project_workspace_dbs(&project_path)? {
+        let workspace_id = workspace_id_from_db_path(&db_path);
+        deleted += delete_composers(&db_path, &project_path, &workspace_id, DeleteTarget::All)?;
     }
-    
-    Ok(deleted_count)
+    Ok(deleted)
 }
 
-#[tauri::command]
-fn delete_workspace_chats(workspace_id: String) -> Result<i64, String> {
-    let workspace_storage = get_cursor_user_path().join("workspaceStorage");
+#[cfg_attr(feature = "gui", tauri::command)]
+pub fn delete_workspace_chats(workspace_id: String) -> Result<i64, AppError> {
+    validate_workspace_id(&workspace_id)?;
+    let workspace_storage = get_cursor_user_path()?.join("workspaceStorage");
     let ws_path = workspace_storage.join(&workspace_id);
     let db_path = ws_path.join("state.vscdb");
     
     if !db_path.exists() {
-        return Err(format!("找不到工作区 {} 的数据库", workspace_id));
+        return Err(AppError::NotFound(format!("找不到工作区 {} 的数据库", workspace_id)));
     }
     
     // 获取工作区的项目路径用于垃圾桶记录
@@ -952,7 +1262,7 @@ fn delete_workspace_chats(workspace_id: String) -> Result<i64, String> {
     let project_path = if let Ok(content) = fs::read_to_string(&ws_json) {
         if let Ok(data) = serde_json::from_str::<serde_json::Value>(&content) {
             if let Some(folder) = data.get("folder").and_then(|v| v.as_str()) {
-                folder.replace("file://", "").replace("%20", " ")
+                workspace_uri::uri_to_path(folder)
             } else {
                 format!("[工作区] {}", workspace_id)
             }
@@ -963,67 +1273,207 @@ fn delete_workspace_chats(workspace_id: String) -> Result<i64, String> {
         format!("[工作区] {}", workspace_id)
     };
     
-    let conn = Connection::open(&db_path).map_err(|e| e.to_string())?;
+    delete_composers(&db_path, &project_path, &workspace_id, DeleteTarget::All)
+}
+
+// ==================== 恢复功能 ====================
+
+/// 定位垃圾桶记录对应的工作区数据库：优先使用记录的工作区 ID，其次按项目路径查找
+fn find_restore_target_db(item: &TrashItem) -> Result<PathBuf, AppError> {
+    if !item.workspace_id.is_empty() {
+        validate_workspace_id(&item.workspace_id)?;
+        let db_path = get_cursor_user_path()?
+            .join("workspaceStorage")
+            .join(&item.workspace_id)
+            .join("state.vscdb");
+        if db_path.exists() {
+            return Ok(db_path);
+        }
+    }
     
-    // 读取现有的 composerData
-    let mut stmt = conn.prepare("SELECT value FROM ItemTable WHERE key = 'composer.composerData'")
-        .map_err(|e| e.to_string())?;
+    find_workspace_dbs_by_project(&item.project_path)?.into_iter().next().ok_or_else(|| {
+        AppError::NotFound(if item.workspace_id.is_empty() {
+            format!("项目 {} 的工作区已不存在", item.project_path)
+        } else {
+            format!("工作区 {} (项目 {}) 已不存在", item.workspace_id, item.project_path)
+        })
+    })
+}
+
+/// 在一个事务中恢复一条垃圾桶记录：写回 composerData、写回 cursorDiskKV、删除垃圾桶记录。
+/// 与删除相同，垃圾桶库和全局库通过 ATTACH 挂到同一连接上，任一步失败都会整体回滚
+fn restore_trash_item(item: &TrashItem, global_db: &Path) -> Result<(), AppError> {
+    // 孤立数据不属于任何工作区，只写回全局库
+    let orphan = item.project_path == kv_orphans::ORPHAN_PROJECT_PATH;
+    let db_path = if orphan {
+        global_db.to_path_buf()
+    } else {
+        let db_path = find_restore_target_db(item)?;
+        cursor_guard::ensure_writable(&db_path)?;
+        snapshot::take_snapshot(&db_path, "恢复会话之前")?;
+        db_path
+    };
     
-    let value: String = stmt.query_row([], |row| row.get(0))
-        .map_err(|e| e.to_string())?;
+    let mut conn = Connection::open(&db_path)?;
+    conn.execute("ATTACH DATABASE ?1 AS trash", [get_trash_db_path()?.to_string_lossy()])?;
+    let has_global = !orphan && global_db.exists();
+    if has_global {
+        conn.execute("ATTACH DATABASE ?1 AS global", [global_db.to_string_lossy()])?;
+    }
     
-    // 解析 JSON
-    let mut data: serde_json::Value = serde_json::from_str(&value)
-        .map_err(|e| e.to_string())?;
+    let tx = conn.transaction()?;
+    if !orphan {
+        restore_composer_header(&tx, item)?;
+    }
+    
+    let rows = {
+        let mut stmt = tx.prepare("SELECT source, key, value FROM trash.trash_kv WHERE trash_id = ? ORDER BY id")?;
+        let rows = stmt.query_map([item.id], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?, row.get::<_, Value>(2)?))
+        })?;
+        rows.collect::<Result<Vec<_>, _>>()?
+    };
+    for (source, key, value) in &rows {
+        // 孤立数据的主库就是全局库
+        let schema = match source.as_str() {
+            "workspace" if orphan => return Err(AppError::NotFound("找不到数据所属的工作区".to_string())),
+            "workspace" => "main",
+            _ if orphan => "main",
+            _ if has_global => "global",
+            _ => return Err(AppError::NotFound("全局数据库不存在".to_string())),
+        };
+        tx.execute(
+            &format!("INSERT OR REPLACE INTO {}.cursorDiskKV (key, value) VALUES (?1, ?2)", schema),
+            rusqlite::params![key, value],
+        )?;
+    }
+    
+    tx.execute("DELETE FROM trash.trash_kv WHERE trash_id = ?", [item.id])?;
+    tx.execute("DELETE FROM trash.trash WHERE id = ?", [item.id])?;
+    tx.commit()?;
+    
+    Ok(())
+}
+
+/// 把会话写回工作区 composerData，工作区里还没有会话时从空列表开始
+fn restore_composer_header(conn: &Connection, item: &TrashItem) -> Result<(), AppError> {
+    let composer: serde_json::Value = serde_json::from_str(&item.original_data)
+        .map_err(|e| AppError::InvalidData(format!("垃圾桶中的会话数据已损坏: {}", e)))?;
+    
+    let value: Option<String> = conn
+        .query_row("SELECT value FROM main.ItemTable WHERE key = 'composer.composerData'", [], |row| row.get(0))
+        .map(Some)
+        .or_else(|e| match e {
+            rusqlite::Error::QueryReturnedNoRows => Ok(None),
+            e => Err(e),
+        })?;
+    
+    let mut data: serde_json::Value = match &value {
+        Some(v) => serde_json::from_str(v)?,
+        None => serde_json::json!({ "allComposers": [] }),
+    };
     
-    // 收集所有要删除的会话 ID 并保存到垃圾桶
-    let mut deleted_ids: Vec<String> = Vec::new();
+    if !data.get("allComposers").map(|v| v.is_array()).unwrap_or(false) {
+        data["allComposers"] = serde_json::json!([]);
+    }
+    
+    let placement = conn.query_row(
+        "SELECT original_index, was_selected, was_focused FROM trash.trash WHERE id = ?",
+        [item.id],
+        |row| Ok(ComposerPlacement { index: row.get(0)?, selected: row.get(1)?, focused: row.get(2)? }),
+    )?;
     
     if let Some(composers) = data.get_mut("allComposers").and_then(|v| v.as_array_mut()) {
-        for c in composers.iter() {
-            if c.get("type").and_then(|v| v.as_str()) == Some("head") {
-                if let Some(id) = c.get("composerId").and_then(|v| v.as_str()) {
-                    // 保存到垃圾桶
-                    let _ = add_to_trash(c, &project_path);
-                    deleted_ids.push(id.to_string());
-                }
-            }
+        // 已经存在同 ID 的会话时不重复插入
+        let exists = composers
+            .iter()
+            .any(|c| c.get("composerId").and_then(|v| v.as_str()) == Some(item.chat_id.as_str()));
+        if !exists {
+            // 放回原来的位置，期间列表变短时放到末尾
+            let index = placement
+                .index
+                .map_or(composers.len(), |i| (i.max(0) as usize).min(composers.len()));
+            composers.insert(index, composer);
         }
-        
-        // 清空所有会话
-        composers.clear();
     }
     
-    let deleted_count = deleted_ids.len() as i64;
+    for (key, restore) in COMPOSER_ID_LISTS.into_iter().zip([placement.selected, placement.focused]) {
+        if !restore {
+            continue;
+        }
+        if !data.get(key).map(|v| v.is_array()).unwrap_or(false) {
+            data[key] = serde_json::json!([]);
+        }
+        if let Some(ids) = data.get_mut(key).and_then(|v| v.as_array_mut()) {
+            if !ids.iter().any(|v| v.as_str() == Some(item.chat_id.as_str())) {
+                ids.push(serde_json::Value::String(item.chat_id.clone()));
+            }
+        }
+    }
     
-    // 写回数据库
-    let new_value = serde_json::to_string(&data).map_err(|e| e.to_string())?;
+    let new_value = serde_json::to_string(&data)?;
     conn.execute(
-        "UPDATE ItemTable SET value = ? WHERE key = 'composer.composerData'",
+        "INSERT OR REPLACE INTO main.ItemTable (key, value) VALUES ('composer.composerData', ?)",
         [&new_value],
-    ).map_err(|e| e.to_string())?;
-    
-    // 删除相关的 bubble 和 checkpoint 数据
-    for id in &deleted_ids {
-        let _ = conn.execute(
-            "DELETE FROM cursorDiskKV WHERE key LIKE ?",
-            [format!("bubbleId:{}:%", id)],
-        );
-        let _ = conn.execute(
-            "DELETE FROM cursorDiskKV WHERE key LIKE ?",
-            [format!("checkpointId:{}:%", id)],
-        );
+    )?;
+    Ok(())
+}
+
+#[cfg_attr(feature = "gui", tauri::command)]
+pub fn restore_trash_items(ids: Vec<i64>) -> Result<RestoreResult, AppError> {
+    ensure_cleanup_supported()?;
+    let items = get_trash_items().map_err(AppError::Trash)?;
+    
+    // 先检查所有要写入的数据库，Cursor 正在使用任一个时整体拒绝
+    let global_db = get_global_db_path()?;
+    cursor_guard::ensure_writable(&global_db)?;
+    for item in items.iter().filter(|i| ids.contains(&i.id) && i.project_path != kv_orphans::ORPHAN_PROJECT_PATH) {
+        if let Ok(db_path) = find_restore_target_db(item) {
+            cursor_guard::ensure_writable(&db_path)?;
+        }
+    }
+    snapshot::take_snapshot(&global_db, "恢复会话之前")?;
+    init_trash_db().map_err(AppError::Trash)?;
+    
+    let mut restored_count = 0i64;
+    let mut failures = Vec::new();
+    
+    for id in ids {
+        let Some(item) = items.iter().find(|i| i.id == id) else {
+            failures.push(RestoreFailure {
+                trash_id: id,
+                chat_name: String::new(),
+                error: "垃圾桶中不存在该记录".to_string(),
+            });
+            continue;
+        };
+        
+        match restore_trash_item(item, &global_db) {
+            Ok(()) => restored_count += 1,
+            Err(error) => failures.push(RestoreFailure {
+                trash_id: id,
+                chat_name: item.chat_name.clone(),
+                error: error.to_string(),
+            }),
+        }
     }
     
-    Ok(deleted_count)
+    Ok(RestoreResult { restored_count, failures })
 }
 
 // ==================== 应用入口 ====================
 
+#[cfg(feature = "gui")]
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
+    deletion_queue::spawn_worker();
+    
     tauri::Builder::default()
         .plugin(tauri_plugin_opener::init())
+        .setup(|app| {
+            watcher::spawn(app.handle().clone());
+            Ok(())
+        })
         .invoke_handler(tauri::generate_handler![
             get_storage_info,
             get_database_stats,
@@ -1035,10 +1485,47 @@ pub fn run() {
             get_trash_items,
             clear_trash,
             delete_trash_item,
+            restore_trash_items,
             delete_chat,
             delete_chats_batch,
             delete_project_chats,
-            delete_workspace_chats
+            delete_workspace_chats,
+            cursor_guard::get_cursor_status,
+            deletion_queue::queue_deletion,
+            deletion_queue::get_pending_deletions,
+            deletion_queue::cancel_pending_deletion,
+            deletion_queue::apply_pending_deletions,
+            settings::get_settings,
+            settings::set_snapshot_generations,
+            data_root::get_data_root,
+            data_root::set_data_root,
+            editor::get_editor_profiles,
+            editor::get_active_editor,
+            editor::set_editor,
+            editor::get_editor_summaries,
+            history::get_history_report,
+            history_cleanup::clean_history,
+            history_cleanup::list_history_trash,
+            history_cleanup::restore_history_trash,
+            history_cleanup::purge_history_trash,
+            workspace_orphans::scan_orphaned_workspaces,
+            workspace_orphans::remove_orphaned_workspaces,
+            compaction::compact_databases,
+            key_stats::get_key_breakdown,
+            kv_orphans::scan_orphaned_kv,
+            kv_orphans::purge_orphaned_kv,
+            usage_history::record_usage_snapshot,
+            usage_history::get_usage_totals,
+            usage_history::get_storage_trend,
+            activity::get_activity_timeline,
+            timezone::get_timezone,
+            timezone::set_timezone,
+            snapshot::list_snapshots,
+            snapshot::restore_snapshot,
+            snapshot::delete_snapshot,
+            transcript::get_chat_transcript,
+            search::search_chats,
+            search::rebuild_search_index
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
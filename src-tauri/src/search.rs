@@ -0,0 +1,317 @@
+//! 全文搜索：把会话名称、描述和消息内容写入本地 FTS5 索引
+
+use crate::transcript::load_transcript;
+use crate::workspace_scan::{self, ScanSnapshot, WorkspaceScan};
+use crate::AppError;
+use rusqlite::types::Value as SqlValue;
+use rusqlite::Connection;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+/// 默认返回的结果数量
+const DEFAULT_LIMIT: i64 = 50;
+
+/// 片段中关键词前后保留的字符数（trigram 分词下一个字符约为一个词元）
+const SNIPPET_CONTEXT: usize = 32;
+
+/// 上次刷新索引时的扫描结果，按索引文件区分。工作区和全局库都没有变化时跳过刷新
+static LAST_REFRESH: Mutex<Option<(PathBuf, ScanSnapshot)>> = Mutex::new(None);
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct SearchFilters {
+    pub project_path: Option<String>,
+    pub mode: Option<String>,
+    pub limit: Option<i64>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct SearchHit {
+    pub chat_id: String,
+    pub chat_name: String,
+    pub project_path: String,
+    pub workspace_id: String,
+    pub mode: String,
+    pub updated_at: Option<String>,
+    pub updated_at_ms: Option<i64>,
+    pub snippet: String,
+    pub rank: f64,
+}
+
+/// 搜索索引放在本工具的数据目录下，和快照、长期统计一样不写入编辑器的 User 目录，可以随时重建
+fn get_search_db_path() -> Result<PathBuf, AppError> {
+    Ok(crate::get_app_data_path()?.join("search.db"))
+}
+
+fn init_search_db() -> Result<Connection, AppError> {
+    let conn = Connection::open(get_search_db_path()?)?;
+    // trigram 分词可以匹配中文等没有空格分隔的文本
+    conn.execute_batch(
+        "CREATE VIRTUAL TABLE IF NOT EXISTS chat_index USING fts5(
+            chat_id UNINDEXED,
+            project_path UNINDEXED,
+            workspace_id UNINDEXED,
+            mode UNINDEXED,
+            updated_at UNINDEXED,
+            name,
+            subtitle,
+            content,
+            tokenize = 'trigram'
+        );
+        CREATE TABLE IF NOT EXISTS indexed_chats (
+            chat_id TEXT PRIMARY KEY,
+            updated_at TEXT
+        );",
+    )?;
+    Ok(conn)
+}
+
+/// 工作区在索引中记录的项目路径：单项目为项目目录，多项目为 .code-workspace 文件
+fn workspace_project_path(ws: &WorkspaceScan) -> String {
+    ws.folder
+        .clone()
+        .or_else(|| ws.workspace_file.as_ref().map(|p| p.to_string_lossy().to_string()))
+        .unwrap_or_else(|| format!("[工作区] {}", ws.id))
+}
+
+/// 增量更新索引：只重新读取更新时间戳变化的会话，并清理已不存在的会话。
+/// 索引中保存毫秒时间戳，显示用的文字在查询时按当前时区生成
+fn refresh_index(conn: &mut Connection) -> Result<i64, AppError> {
+    let search_db = get_search_db_path()?;
+    let snapshot = workspace_scan::scan()?;
+    {
+        let last = LAST_REFRESH.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some((path, previous)) = last.as_ref() {
+            if *path == search_db && snapshot.unchanged_since(previous) {
+                return Ok(0);
+            }
+        }
+    }
+    
+    let global_db = crate::get_global_db_path()?;
+    let global = if global_db.exists() { Some(Connection::open(&global_db)?) } else { None };
+    
+    let unreadable = snapshot.unreadable_workspaces();
+    
+    let tx = conn.transaction()?;
+    let mut seen = HashSet::new();
+    let mut updated = 0i64;
+    
+    for ws in &snapshot.workspaces {
+        let project_path = workspace_project_path(ws);
+        for chat in &ws.sessions {
+            seen.insert(chat.id.clone());
+            
+            // 旧版本保存的是格式化后的时间，和时间戳对不上，会重建一次
+            let marker = chat.updated_at_ms.map(|ms| ms.to_string());
+            let indexed_at: Option<Option<String>> = tx
+                .query_row("SELECT updated_at FROM indexed_chats WHERE chat_id = ?", [&chat.id], |row| row.get(0))
+                .ok();
+            if indexed_at.as_ref() == Some(&marker) {
+                continue;
+            }
+            
+            let content = global
+                .as_ref()
+                .and_then(|g| load_transcript(g, &chat.id).ok())
+                .map(|t| {
+                    t.messages
+                        .iter()
+                        .map(|m| m.text.as_str())
+                        .filter(|s| !s.is_empty())
+                        .collect::<Vec<_>>()
+                        .join("\n")
+                })
+                .unwrap_or_default();
+            
+            tx.execute("DELETE FROM chat_index WHERE chat_id = ?", [&chat.id])?;
+            tx.execute(
+                "INSERT INTO chat_index (chat_id, project_path, workspace_id, mode, updated_at, name, subtitle, content)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+                rusqlite::params![chat.id, project_path, ws.id, chat.mode, chat.updated_at_ms, chat.name, chat.subtitle, content],
+            )?;
+            tx.execute(
+                "INSERT OR REPLACE INTO indexed_chats (chat_id, updated_at) VALUES (?1, ?2)",
+                rusqlite::params![chat.id, marker],
+            )?;
+            updated += 1;
+        }
+    }
+    
+    // 清理已被删除的会话，本次没能读取的工作区中的会话保留原索引
+    let stale: Vec<String> = {
+        let mut stmt = tx.prepare("SELECT chat_id, workspace_id FROM chat_index")?;
+        let ids = stmt.query_map([], |row| Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?)))?;
+        let kept: HashSet<String> = ids
+            .filter_map(|id| id.ok())
+            .filter(|(_, ws_id)| unreadable.contains(ws_id))
+            .map(|(chat_id, _)| chat_id)
+            .collect();
+        let mut stmt = tx.prepare("SELECT chat_id FROM indexed_chats")?;
+        let ids = stmt.query_map([], |row| row.get::<_, String>(0))?;
+        ids.filter_map(|id| id.ok()).filter(|id| !seen.contains(id) && !kept.contains(id)).collect()
+    };
+    for id in &stale {
+        tx.execute("DELETE FROM chat_index WHERE chat_id = ?", [id])?;
+        tx.execute("DELETE FROM indexed_chats WHERE chat_id = ?", [id])?;
+    }
+    
+    tx.commit()?;
+    *LAST_REFRESH.lock().unwrap_or_else(|e| e.into_inner()) = Some((search_db, snapshot));
+    Ok(updated)
+}
+
+/// 把查询词转换为 FTS5 查询：每个词加引号，词之间为 AND
+fn to_fts_query(terms: &[&str]) -> String {
+    terms
+        .iter()
+        .map(|term| format!("\"{}\"", term.replace('"', "\"\"")))
+        .collect::<Vec<_>>()
+        .join(" AND ")
+}
+
+/// 包含 `term` 的 LIKE 模式，`%`、`_` 按字面匹配，配合 `ESCAPE '\'` 使用
+fn like_pattern(term: &str) -> String {
+    let escaped = term.replace('\\', "\\\\").replace('%', "\\%").replace('_', "\\_");
+    format!("%{}%", escaped)
+}
+
+/// LIKE 匹配时没有 snippet()，手动截取关键词前后的片段并高亮
+fn like_snippet(texts: &[&str], query: &str) -> String {
+    let needle = query.to_ascii_lowercase();
+    
+    for text in texts {
+        let Some(start) = text.to_ascii_lowercase().find(&needle) else {
+            continue;
+        };
+        let end = start + needle.len();
+        let mut before: Vec<char> = text[..start].chars().rev().take(SNIPPET_CONTEXT).collect();
+        before.reverse();
+        let before: String = before.into_iter().collect();
+        let after: String = text[end..].chars().take(SNIPPET_CONTEXT).collect();
+        let prefix = if before.len() < start { "…" } else { "" };
+        let suffix = if after.len() < text.len() - end { "…" } else { "" };
+        return format!("{}{}<mark>{}</mark>{}{}", prefix, before, &text[start..end], after, suffix);
+    }
+    
+    String::new()
+}
+
+#[cfg_attr(feature = "gui", tauri::command)]
+pub fn rebuild_search_index() -> Result<i64, AppError> {
+    let mut conn = init_search_db()?;
+    conn.execute_batch("DELETE FROM chat_index; DELETE FROM indexed_chats;")?;
+    *LAST_REFRESH.lock().unwrap_or_else(|e| e.into_inner()) = None;
+    refresh_index(&mut conn)
+}
+
+#[cfg_attr(feature = "gui", tauri::command)]
+pub fn search_chats(query: String, filters: Option<SearchFilters>) -> Result<Vec<SearchHit>, AppError> {
+    let filters = filters.unwrap_or_default();
+    let mut conn = init_search_db()?;
+    refresh_index(&mut conn)?;
+    
+    let query = query.trim();
+    if query.is_empty() {
+        return Ok(Vec::new());
+    }
+    
+    // trigram 至少需要 3 个字符，更短的词单独用 LIKE 匹配，和其余词的全文匹配取交集
+    let (short_terms, long_terms): (Vec<&str>, Vec<&str>) =
+        query.split_whitespace().partition(|t| t.chars().count() < 3);
+    let like_only = long_terms.is_empty();
+    
+    let mut conditions = Vec::new();
+    let mut params: Vec<SqlValue> = Vec::new();
+    if !like_only {
+        params.push(SqlValue::Text(to_fts_query(&long_terms)));
+        conditions.push(format!("chat_index MATCH ?{}", params.len()));
+    }
+    for term in &short_terms {
+        params.push(SqlValue::Text(like_pattern(term)));
+        let n = params.len();
+        conditions.push(format!(
+            "(name LIKE ?{n} ESCAPE '\\' OR subtitle LIKE ?{n} ESCAPE '\\' OR content LIKE ?{n} ESCAPE '\\')"
+        ));
+    }
+    params.push(filters.project_path.map_or(SqlValue::Null, SqlValue::Text));
+    let project_param = params.len();
+    params.push(filters.mode.map_or(SqlValue::Null, SqlValue::Text));
+    let mode_param = params.len();
+    params.push(SqlValue::Integer(filters.limit.unwrap_or(DEFAULT_LIMIT)));
+    let limit_param = params.len();
+    
+    // 只有 LIKE 条件时没有相关度，按更新时间排序
+    let (snippet, rank, order) = if like_only {
+        ("''".to_string(), "0.0", "updated_at DESC")
+    } else {
+        (
+            format!("snippet(chat_index, -1, '<mark>', '</mark>', '…', {})", SNIPPET_CONTEXT * 2),
+            "bm25(chat_index, 0, 0, 0, 0, 0, 10.0, 5.0, 1.0)",
+            "8",
+        )
+    };
+    
+    let sql = format!(
+        "SELECT chat_id, name, project_path, workspace_id, mode, updated_at, {snippet}, {rank}, subtitle, content
+         FROM chat_index
+         WHERE {} AND (?{project_param} IS NULL OR project_path = ?{project_param})
+           AND (?{mode_param} IS NULL OR mode = ?{mode_param})
+         ORDER BY {order} LIMIT ?{limit_param}",
+        conditions.join(" AND ")
+    );
+    
+    let mut stmt = conn.prepare(&sql)?;
+    let hits = stmt.query_map(
+        rusqlite::params_from_iter(params),
+        |row| {
+            let name: String = row.get(1)?;
+            let mut snippet: String = row.get(6)?;
+            if like_only {
+                let subtitle: String = row.get(8)?;
+                let content: String = row.get(9)?;
+                snippet = like_snippet(&[&name, &subtitle, &content], short_terms[0]);
+            }
+            let updated_at_ms: Option<i64> = row.get(5).ok().flatten();
+            Ok(SearchHit {
+                chat_id: row.get(0)?,
+                chat_name: name,
+                project_path: row.get(2)?,
+                workspace_id: row.get(3)?,
+                mode: row.get(4)?,
+                updated_at: updated_at_ms.and_then(crate::timestamp_to_string),
+                updated_at_ms,
+                snippet,
+                rank: row.get(7)?,
+            })
+        },
+    )?;
+    
+    Ok(hits.collect::<Result<Vec<_>, _>>()?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn like_pattern_matches_wildcards_literally() {
+        let conn = Connection::open_in_memory().unwrap();
+        let matches = |text: &str, term: &str| -> bool {
+            conn.query_row("SELECT ?1 LIKE ?2 ESCAPE '\\'", [text, &like_pattern(term)], |row| row.get(0))
+                .unwrap()
+        };
+        assert!(matches("占用 50% 空间", "50%"));
+        assert!(!matches("占用 500 空间", "50%"));
+        assert!(matches("a_b", "_b"));
+        assert!(!matches("axb", "_b"));
+        assert!(matches(r"C:\path", r"C:\"));
+        assert!(!matches(r"C:/path", r"C:\"));
+    }
+    
+    #[test]
+    fn fts_query_quotes_terms() {
+        assert_eq!(to_fts_query(&["hello", "wor\"ld"]), "\"hello\" AND \"wor\"\"ld\"");
+    }
+}
@@ -0,0 +1,86 @@
+//! 应用设置，保存在系统配置目录下的 settings.json
+
+use crate::AppError;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+use std::sync::{Arc, RwLock};
+
+/// 默认保留的快照代数
+const DEFAULT_SNAPSHOT_GENERATIONS: usize = 5;
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub struct Settings {
+    /// 每个数据库保留的快照数量，0 表示不做快照
+    pub snapshot_generations: usize,
+    /// 自定义的 Cursor User 目录，None 表示使用系统默认路径
+    pub data_root: Option<String>,
+    /// 分析的编辑器 ID，None 表示 Cursor
+    pub editor: Option<String>,
+    /// 显示时间使用的 IANA 时区，None 表示使用系统时区
+    pub timezone: Option<String>,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Settings {
+            snapshot_generations: DEFAULT_SNAPSHOT_GENERATIONS,
+            data_root: None,
+            editor: None,
+            timezone: None,
+        }
+    }
+}
+
+fn settings_path() -> Option<PathBuf> {
+    dirs::config_dir().map(|dir| dir.join("cursor-analysis").join("settings.json"))
+}
+
+/// 进程内缓存的设置。数据目录和编辑器每次查询都要用到设置，只在第一次读取文件，保存时更新
+static CACHE: RwLock<Option<Arc<Settings>>> = RwLock::new(None);
+
+/// 当前设置，文件不存在或损坏时使用默认值
+pub fn current() -> Arc<Settings> {
+    if let Some(settings) = CACHE.read().unwrap_or_else(|e| e.into_inner()).as_ref() {
+        return settings.clone();
+    }
+    let settings = Arc::new(read_file());
+    *CACHE.write().unwrap_or_else(|e| e.into_inner()) = Some(settings.clone());
+    settings
+}
+
+/// 读取设置的副本，修改后用 `save` 保存
+pub fn load() -> Settings {
+    (*current()).clone()
+}
+
+fn read_file() -> Settings {
+    settings_path()
+        .and_then(|path| fs::read_to_string(path).ok())
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+pub fn save(settings: &Settings) -> Result<(), AppError> {
+    let path = settings_path().ok_or_else(|| AppError::Io("找不到系统配置目录".to_string()))?;
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    fs::write(&path, serde_json::to_string_pretty(settings)?)?;
+    *CACHE.write().unwrap_or_else(|e| e.into_inner()) = Some(Arc::new(settings.clone()));
+    Ok(())
+}
+
+#[cfg_attr(feature = "gui", tauri::command)]
+pub fn get_settings() -> Settings {
+    load()
+}
+
+#[cfg_attr(feature = "gui", tauri::command)]
+pub fn set_snapshot_generations(generations: usize) -> Result<Settings, AppError> {
+    let mut settings = load();
+    settings.snapshot_generations = generations;
+    save(&settings)?;
+    Ok(settings)
+}
@@ -0,0 +1,156 @@
+//! 修改前快照：写入 state.vscdb 之前用 SQLite 在线备份 API 复制一份，出问题时可以原样回滚
+
+use crate::{cursor_guard, settings, AppError};
+use chrono::Utc;
+use rusqlite::{Connection, DatabaseName};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Snapshot {
+    pub id: i64,
+    pub source_path: String,
+    pub snapshot_path: String,
+    pub created_at: String,
+    pub size: u64,
+    pub reason: String,
+}
+
+/// 快照目录，放在本工具的数据目录下
+fn snapshot_dir() -> Result<PathBuf, AppError> {
+    Ok(crate::get_app_data_path()?.join("snapshots"))
+}
+
+fn init_snapshot_db() -> Result<Connection, AppError> {
+    let conn = Connection::open(crate::get_trash_db_path()?)?;
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS snapshots (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            source_path TEXT NOT NULL,
+            snapshot_path TEXT NOT NULL,
+            created_at TEXT NOT NULL,
+            size INTEGER DEFAULT 0,
+            reason TEXT
+        )",
+        [],
+    )?;
+    Ok(conn)
+}
+
+/// 快照文件名前缀：工作区库用工作区 ID，全局库用 global
+fn source_label(db_path: &Path) -> Result<String, AppError> {
+    if db_path == crate::get_global_db_path()? {
+        Ok("global".to_string())
+    } else {
+        Ok(crate::workspace_id_from_db_path(db_path))
+    }
+}
+
+fn load_snapshots(conn: &Connection, source_path: Option<&str>) -> Result<Vec<Snapshot>, AppError> {
+    let mut stmt = conn.prepare(
+        "SELECT id, source_path, snapshot_path, created_at, size, reason FROM snapshots
+         WHERE ?1 IS NULL OR source_path = ?1 ORDER BY id DESC",
+    )?;
+    let rows = stmt.query_map([source_path], |row| {
+        Ok(Snapshot {
+            id: row.get(0)?,
+            source_path: row.get(1)?,
+            snapshot_path: row.get(2)?,
+            created_at: row.get(3)?,
+            size: row.get::<_, i64>(4)? as u64,
+            reason: row.get::<_, Option<String>>(5)?.unwrap_or_default(),
+        })
+    })?;
+    Ok(rows.collect::<Result<Vec<_>, _>>()?)
+}
+
+/// 只保留最近 generations 份快照
+fn prune(conn: &Connection, source_path: &str, generations: usize) -> Result<(), AppError> {
+    for old in load_snapshots(conn, Some(source_path))?.into_iter().skip(generations) {
+        let _ = fs::remove_file(&old.snapshot_path);
+        conn.execute("DELETE FROM snapshots WHERE id = ?", [old.id])?;
+    }
+    Ok(())
+}
+
+/// 写入前为数据库做一次快照，设置中快照代数为 0 时跳过
+pub fn take_snapshot(db_path: &Path, reason: &str) -> Result<Option<Snapshot>, AppError> {
+    let generations = settings::load().snapshot_generations;
+    if generations == 0 || !db_path.exists() {
+        return Ok(None);
+    }
+    
+    let dir = snapshot_dir()?;
+    fs::create_dir_all(&dir)?;
+    
+    let now = Utc::now();
+    let file_name = format!("{}-{}.vscdb", source_label(db_path)?, now.format("%Y%m%d%H%M%S%3f"));
+    let snapshot_path = dir.join(file_name);
+    
+    let src = Connection::open(db_path)?;
+    src.backup(DatabaseName::Main, &snapshot_path, None)?;
+    
+    let size = fs::metadata(&snapshot_path).map(|m| m.len()).unwrap_or(0);
+    let source_path = db_path.to_string_lossy().to_string();
+    let snapshot_path = snapshot_path.to_string_lossy().to_string();
+    let created_at = now.format("%Y-%m-%d %H:%M:%S").to_string();
+    
+    let conn = init_snapshot_db()?;
+    conn.execute(
+        "INSERT INTO snapshots (source_path, snapshot_path, created_at, size, reason) VALUES (?1, ?2, ?3, ?4, ?5)",
+        rusqlite::params![source_path, snapshot_path, created_at, size as i64, reason],
+    )?;
+    let snapshot = Snapshot {
+        id: conn.last_insert_rowid(),
+        source_path: source_path.clone(),
+        snapshot_path,
+        created_at,
+        size,
+        reason: reason.to_string(),
+    };
+    
+    prune(&conn, &source_path, generations)?;
+    
+    Ok(Some(snapshot))
+}
+
+#[cfg_attr(feature = "gui", tauri::command)]
+pub fn list_snapshots(source_path: Option<String>) -> Result<Vec<Snapshot>, AppError> {
+    load_snapshots(&init_snapshot_db()?, source_path.as_deref())
+}
+
+/// 用快照覆盖原数据库，覆盖前会先给当前状态再做一次快照
+#[cfg_attr(feature = "gui", tauri::command)]
+pub fn restore_snapshot(snapshot_id: i64) -> Result<Snapshot, AppError> {
+    let conn = init_snapshot_db()?;
+    let snapshot = load_snapshots(&conn, None)?
+        .into_iter()
+        .find(|s| s.id == snapshot_id)
+        .ok_or_else(|| AppError::NotFound(format!("快照 {} 不存在", snapshot_id)))?;
+    
+    let snapshot_path = PathBuf::from(&snapshot.snapshot_path);
+    if !snapshot_path.exists() {
+        return Err(AppError::NotFound(format!("快照文件 {} 已丢失", snapshot.snapshot_path)));
+    }
+    
+    let target = PathBuf::from(&snapshot.source_path);
+    cursor_guard::ensure_writable(&target)?;
+    take_snapshot(&target, &format!("恢复快照 #{} 之前", snapshot.id))?;
+    
+    let mut dst = Connection::open(&target)?;
+    dst.restore(DatabaseName::Main, &snapshot_path, None::<fn(rusqlite::backup::Progress)>)?;
+    
+    Ok(snapshot)
+}
+
+#[cfg_attr(feature = "gui", tauri::command)]
+pub fn delete_snapshot(snapshot_id: i64) -> Result<bool, AppError> {
+    let conn = init_snapshot_db()?;
+    let Some(snapshot) = load_snapshots(&conn, None)?.into_iter().find(|s| s.id == snapshot_id) else {
+        return Ok(false);
+    };
+    let _ = fs::remove_file(&snapshot.snapshot_path);
+    conn.execute("DELETE FROM snapshots WHERE id = ?", [snapshot_id])?;
+    Ok(true)
+}
@@ -0,0 +1,207 @@
+//! 显示时间使用的时区，优先级：命令行 > 设置文件 > 系统时区。
+//!
+//! 会话和垃圾桶同时保留毫秒时间戳，排序和时间范围过滤只看时间戳，这里只负责把时间戳转成日期和文字。
+
+use crate::{settings, AppError};
+use chrono::{Duration, Local, NaiveDate, TimeZone, Utc};
+use chrono_tz::Tz;
+use serde::Serialize;
+use std::sync::RwLock;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Zone {
+    /// 系统时区
+    Local,
+    /// IANA 时区，如 Asia/Shanghai、UTC
+    Named(Tz),
+}
+
+impl Zone {
+    pub fn name(&self) -> String {
+        match self {
+            Zone::Local => "local".to_string(),
+            Zone::Named(tz) => tz.name().to_string(),
+        }
+    }
+    
+    /// 毫秒时间戳按 `fmt` 格式化
+    pub fn format(&self, ms: i64, fmt: &str) -> Option<String> {
+        match self {
+            Zone::Local => format_in(&Local, ms, fmt),
+            Zone::Named(tz) => format_in(tz, ms, fmt),
+        }
+    }
+    
+    /// 毫秒时间戳在该时区的日期
+    pub fn date(&self, ms: i64) -> Option<NaiveDate> {
+        match self {
+            Zone::Local => Local.timestamp_millis_opt(ms).single().map(|dt| dt.date_naive()),
+            Zone::Named(tz) => tz.timestamp_millis_opt(ms).single().map(|dt| dt.date_naive()),
+        }
+    }
+    
+    pub fn today(&self) -> NaiveDate {
+        let now = Utc::now();
+        self.date(now.timestamp_millis()).unwrap_or(now.date_naive())
+    }
+    
+    /// 该时区某一天开始时的毫秒时间戳
+    pub fn start_of_day_ms(&self, date: NaiveDate) -> i64 {
+        match self {
+            Zone::Local => start_of_day_in(&Local, date),
+            Zone::Named(tz) => start_of_day_in(tz, date),
+        }
+    }
+    
+    /// 当前相对 UTC 的偏移，如 +08:00
+    pub fn utc_offset(&self) -> String {
+        self.format(Utc::now().timestamp_millis(), "%:z").unwrap_or_default()
+    }
+}
+
+fn format_in<T: TimeZone>(tz: &T, ms: i64, fmt: &str) -> Option<String>
+where
+    T::Offset: std::fmt::Display,
+{
+    tz.timestamp_millis_opt(ms).single().map(|dt| dt.format(fmt).to_string())
+}
+
+fn start_of_day_in<T: TimeZone>(tz: &T, date: NaiveDate) -> i64 {
+    let midnight = date.and_hms_opt(0, 0, 0).unwrap_or_default();
+    // 夏令时从 00:00 开始时当天没有 00:00，取之后第一个有效的整点
+    (0..3)
+        .find_map(|h| tz.from_local_datetime(&(midnight + Duration::hours(h))).earliest())
+        .map(|dt| dt.timestamp_millis())
+        .unwrap_or_else(|| midnight.and_utc().timestamp_millis())
+}
+
+static OVERRIDE: RwLock<Option<Zone>> = RwLock::new(None);
+/// 设置文件中的时区，第一次使用时读取，修改设置时同步更新
+static CONFIGURED: RwLock<Option<Zone>> = RwLock::new(None);
+
+/// 空字符串和 local 表示系统时区
+fn parse(name: &str) -> Result<Zone, AppError> {
+    match name.trim() {
+        "" | "local" => Ok(Zone::Local),
+        name => name
+            .parse::<Tz>()
+            .map(Zone::Named)
+            .map_err(|_| AppError::InvalidData(format!("未知的时区: {}，应为 IANA 名称，如 Asia/Shanghai", name))),
+    }
+}
+
+/// 设置进程内使用的时区（命令行参数使用）
+pub fn set_override(name: &str) -> Result<(), AppError> {
+    let zone = parse(name)?;
+    *OVERRIDE.write().unwrap_or_else(|e| e.into_inner()) = Some(zone);
+    Ok(())
+}
+
+/// 当前使用的时区
+pub fn current() -> Zone {
+    if let Some(zone) = *OVERRIDE.read().unwrap_or_else(|e| e.into_inner()) {
+        return zone;
+    }
+    if let Some(zone) = *CONFIGURED.read().unwrap_or_else(|e| e.into_inner()) {
+        return zone;
+    }
+    // 设置文件中的时区无效时退回系统时区
+    let zone = settings::load()
+        .timezone
+        .and_then(|name| parse(&name).ok())
+        .unwrap_or(Zone::Local);
+    *CONFIGURED.write().unwrap_or_else(|e| e.into_inner()) = Some(zone);
+    zone
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct TimezoneInfo {
+    /// 设置文件中的时区，None 表示使用系统时区
+    pub configured: Option<String>,
+    /// 实际使用的时区，系统时区为 local
+    pub effective: String,
+    pub utc_offset: String,
+}
+
+#[cfg_attr(feature = "gui", tauri::command)]
+pub fn get_timezone() -> TimezoneInfo {
+    let zone = current();
+    TimezoneInfo {
+        configured: settings::load().timezone,
+        effective: zone.name(),
+        utc_offset: zone.utc_offset(),
+    }
+}
+
+/// 设置显示时间使用的时区，None 或空字符串恢复为系统时区
+#[cfg_attr(feature = "gui", tauri::command)]
+pub fn set_timezone(timezone: Option<String>) -> Result<TimezoneInfo, AppError> {
+    let timezone = timezone.map(|t| t.trim().to_string()).filter(|t| !t.is_empty() && t != "local");
+    let zone = match &timezone {
+        Some(name) => parse(name)?,
+        None => Zone::Local,
+    };
+    
+    let mut current = settings::load();
+    current.timezone = timezone;
+    settings::save(&current)?;
+    *CONFIGURED.write().unwrap_or_else(|e| e.into_inner()) = Some(zone);
+    
+    Ok(get_timezone())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    /// 2023-11-14 22:13:20 UTC
+    const MS: i64 = 1_700_000_000_000;
+    
+    fn named(name: &str) -> Zone {
+        parse(name).unwrap()
+    }
+    
+    #[test]
+    fn parse_accepts_local_and_iana_names() {
+        assert_eq!(parse("").unwrap(), Zone::Local);
+        assert_eq!(parse(" local ").unwrap(), Zone::Local);
+        assert_eq!(named(" UTC ").name(), "UTC");
+        assert_eq!(named("Asia/Shanghai").name(), "Asia/Shanghai");
+        assert!(matches!(parse("Mars/Olympus"), Err(AppError::InvalidData(_))));
+    }
+    
+    #[test]
+    fn format_uses_zone_offset() {
+        let fmt = "%Y-%m-%d %H:%M:%S";
+        assert_eq!(named("UTC").format(MS, fmt).as_deref(), Some("2023-11-14 22:13:20"));
+        assert_eq!(named("Asia/Shanghai").format(MS, fmt).as_deref(), Some("2023-11-15 06:13:20"));
+        assert_eq!(named("America/New_York").format(MS, fmt).as_deref(), Some("2023-11-14 17:13:20"));
+        assert_eq!(named("UTC").utc_offset(), "+00:00");
+    }
+    
+    #[test]
+    fn date_follows_zone() {
+        let date = |value: &str| NaiveDate::parse_from_str(value, "%Y-%m-%d").unwrap();
+        assert_eq!(named("UTC").date(MS), Some(date("2023-11-14")));
+        assert_eq!(named("Asia/Shanghai").date(MS), Some(date("2023-11-15")));
+    }
+    
+    #[test]
+    fn start_of_day_in_zone() {
+        let day = NaiveDate::from_ymd_opt(2023, 11, 15).unwrap();
+        let shanghai = named("Asia/Shanghai");
+        // 上海 00:00 是前一天 UTC 16:00
+        assert_eq!(shanghai.start_of_day_ms(day), MS - (6 * 3600 + 13 * 60 + 20) * 1000);
+        assert_eq!(shanghai.date(shanghai.start_of_day_ms(day)), Some(day));
+        assert_eq!(shanghai.date(shanghai.start_of_day_ms(day) - 1), day.pred_opt());
+    }
+    
+    #[test]
+    fn start_of_day_skips_missing_midnight() {
+        // 圣地亚哥 2023-09-03 00:00 进入夏令时，直接跳到 01:00
+        let santiago = named("America/Santiago");
+        let day = NaiveDate::from_ymd_opt(2023, 9, 3).unwrap();
+        let start = santiago.start_of_day_ms(day);
+        assert_eq!(santiago.format(start, "%Y-%m-%d %H:%M").as_deref(), Some("2023-09-03 01:00"));
+    }
+}
@@ -0,0 +1,287 @@
+//! 会话全文：从全局库 cursorDiskKV 的 bubbleId:<composerId>:* 中读取每条消息
+
+use crate::AppError;
+use chrono::DateTime;
+use rusqlite::types::Value;
+use rusqlite::Connection;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum MessageRole {
+    User,
+    Assistant,
+    Unknown,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct CodeBlock {
+    pub language: String,
+    pub content: String,
+    pub file_path: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ToolCall {
+    pub name: String,
+    pub status: String,
+    pub arguments: Option<String>,
+    pub result: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ChatMessage {
+    pub bubble_id: String,
+    pub role: MessageRole,
+    pub text: String,
+    pub thinking: Option<String>,
+    pub created_at: Option<String>,
+    pub code_blocks: Vec<CodeBlock>,
+    pub tool_calls: Vec<ToolCall>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ChatTranscript {
+    pub chat_id: String,
+    pub name: Option<String>,
+    pub messages: Vec<ChatMessage>,
+}
+
+/// cursorDiskKV 的 value 可能以 TEXT 或 BLOB 存储
+pub(crate) fn kv_text(value: Value) -> Option<String> {
+    match value {
+        Value::Text(s) => Some(s),
+        Value::Blob(b) => String::from_utf8(b).ok(),
+        _ => None,
+    }
+}
+
+fn str_field(v: &serde_json::Value, key: &str) -> Option<String> {
+    v.get(key).and_then(|v| v.as_str()).filter(|s| !s.is_empty()).map(|s| s.to_string())
+}
+
+/// 字段可能是字符串也可能是 JSON 对象，统一转成字符串
+fn json_text(v: Option<&serde_json::Value>) -> Option<String> {
+    match v {
+        None | Some(serde_json::Value::Null) => None,
+        Some(serde_json::Value::String(s)) if s.is_empty() => None,
+        Some(serde_json::Value::String(s)) => Some(s.clone()),
+        Some(other) => Some(other.to_string()),
+    }
+}
+
+/// createdAt 有毫秒时间戳和 ISO 字符串两种格式
+fn parse_created_at(v: Option<&serde_json::Value>) -> Option<String> {
+    match v? {
+        serde_json::Value::Number(n) => n.as_i64().and_then(crate::timestamp_to_string),
+        serde_json::Value::String(s) => DateTime::parse_from_rfc3339(s)
+            .ok()
+            .and_then(|dt| crate::timestamp_to_string(dt.timestamp_millis())),
+        _ => None,
+    }
+}
+
+fn parse_code_blocks(bubble: &serde_json::Value) -> Vec<CodeBlock> {
+    bubble
+        .get("codeBlocks")
+        .and_then(|v| v.as_array())
+        .map(|blocks| {
+            blocks
+                .iter()
+                .filter_map(|b| {
+                    let content = str_field(b, "content")?;
+                    Some(CodeBlock {
+                        language: str_field(b, "languageId").unwrap_or_default(),
+                        content,
+                        file_path: b
+                            .get("uri")
+                            .and_then(|u| str_field(u, "path").or_else(|| u.as_str().map(|s| s.to_string()))),
+                    })
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn parse_tool_calls(bubble: &serde_json::Value) -> Vec<ToolCall> {
+    let Some(tool) = bubble.get("toolFormerData").filter(|v| v.is_object()) else {
+        return Vec::new();
+    };
+    vec![ToolCall {
+        name: str_field(tool, "name").unwrap_or_else(|| "unknown".to_string()),
+        status: str_field(tool, "status").unwrap_or_default(),
+        arguments: json_text(tool.get("rawArgs").or(tool.get("params"))),
+        result: json_text(tool.get("result")),
+    }]
+}
+
+fn parse_bubble(bubble_id: &str, bubble: &serde_json::Value) -> ChatMessage {
+    // type: 1 为用户消息，2 为 AI 回复
+    let role = match bubble.get("type").and_then(|v| v.as_i64()) {
+        Some(1) => MessageRole::User,
+        Some(2) => MessageRole::Assistant,
+        _ => MessageRole::Unknown,
+    };
+    
+    ChatMessage {
+        bubble_id: str_field(bubble, "bubbleId").unwrap_or_else(|| bubble_id.to_string()),
+        role,
+        text: str_field(bubble, "text").unwrap_or_default(),
+        thinking: bubble.get("thinking").and_then(|t| str_field(t, "text")),
+        created_at: parse_created_at(bubble.get("createdAt")),
+        code_blocks: parse_code_blocks(bubble),
+        tool_calls: parse_tool_calls(bubble),
+    }
+}
+
+/// 读取会话的全部消息，按 composerData 中记录的对话顺序排列。
+/// 还没有 cursorDiskKV 表的库（如新装的编辑器）返回空的会话
+pub fn load_transcript(conn: &Connection, chat_id: &str) -> Result<ChatTranscript, AppError> {
+    if !crate::has_cursor_disk_kv(conn, "main")? {
+        return Ok(ChatTranscript {
+            chat_id: chat_id.to_string(),
+            name: None,
+            messages: Vec::new(),
+        });
+    }
+    
+    let composer: Option<serde_json::Value> = conn
+        .query_row(
+            "SELECT value FROM cursorDiskKV WHERE key = ?",
+            [format!("composerData:{}", chat_id)],
+            |row| row.get::<_, Value>(0),
+        )
+        .ok()
+        .and_then(kv_text)
+        .and_then(|v| serde_json::from_str(&v).ok());
+    
+    let mut params = Vec::new();
+    let condition = crate::key_prefix_condition(&format!("bubbleId:{}:", chat_id), &mut params);
+    let mut stmt = conn.prepare(&format!("SELECT key, value FROM cursorDiskKV WHERE {} ORDER BY rowid", condition))?;
+    let rows = stmt
+        .query_map(rusqlite::params_from_iter(params.iter()), |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, Value>(1)?))
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+    
+    let mut bubbles: Vec<ChatMessage> = rows
+        .into_iter()
+        .filter_map(|(key, value)| {
+            let bubble_id = key.rsplit(':').next().unwrap_or_default().to_string();
+            let bubble: serde_json::Value = serde_json::from_str(&kv_text(value)?).ok()?;
+            Some(parse_bubble(&bubble_id, &bubble))
+        })
+        .collect();
+    
+    // fullConversationHeadersOnly 记录了消息的真实顺序，没有记录的消息排在最后
+    let order: HashMap<String, usize> = composer
+        .as_ref()
+        .and_then(|c| c.get("fullConversationHeadersOnly"))
+        .and_then(|v| v.as_array())
+        .map(|headers| {
+            headers
+                .iter()
+                .enumerate()
+                .filter_map(|(i, h)| str_field(h, "bubbleId").map(|id| (id, i)))
+                .collect()
+        })
+        .unwrap_or_default();
+    bubbles.sort_by_key(|m| order.get(&m.bubble_id).copied().unwrap_or(usize::MAX));
+    
+    Ok(ChatTranscript {
+        chat_id: chat_id.to_string(),
+        name: composer.as_ref().and_then(|c| str_field(c, "name")),
+        messages: bubbles,
+    })
+}
+
+#[cfg_attr(feature = "gui", tauri::command)]
+pub fn get_chat_transcript(chat_id: String) -> Result<ChatTranscript, AppError> {
+    let db_path = crate::get_global_db_path()?;
+    if !db_path.exists() {
+        return Err(AppError::NotFound("找不到全局数据库".to_string()));
+    }
+    let conn = Connection::open(&db_path)?;
+    load_transcript(&conn, &chat_id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    const CHAT: &str = "c1";
+    
+    fn kv_db(rows: &[(&str, Value)]) -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch("CREATE TABLE cursorDiskKV (key TEXT UNIQUE ON CONFLICT REPLACE, value BLOB)").unwrap();
+        for (key, value) in rows {
+            conn.execute("INSERT INTO cursorDiskKV (key, value) VALUES (?1, ?2)", rusqlite::params![key, value]).unwrap();
+        }
+        conn
+    }
+    
+    fn text(json: &str) -> Value {
+        Value::Text(json.to_string())
+    }
+    
+    fn bubble_ids(transcript: &ChatTranscript) -> Vec<&str> {
+        transcript.messages.iter().map(|m| m.bubble_id.as_str()).collect()
+    }
+    
+    #[test]
+    fn orders_messages_by_conversation_headers() {
+        let conn = kv_db(&[
+            ("bubbleId:c1:b3", text(r#"{"type":2,"text":"third"}"#)),
+            ("bubbleId:c1:b1", text(r#"{"type":1,"text":"first"}"#)),
+            ("bubbleId:c1:b2", text(r#"{"type":2,"text":"second"}"#)),
+            (
+                "composerData:c1",
+                text(r#"{"name":"Chat","fullConversationHeadersOnly":[{"bubbleId":"b1"},{"bubbleId":"b2"},{"bubbleId":"b3"}]}"#),
+            ),
+        ]);
+        
+        let transcript = load_transcript(&conn, CHAT).unwrap();
+        assert_eq!(transcript.name.as_deref(), Some("Chat"));
+        assert_eq!(bubble_ids(&transcript), ["b1", "b2", "b3"]);
+        assert_eq!(transcript.messages[0].role, MessageRole::User);
+        assert_eq!(transcript.messages[1].role, MessageRole::Assistant);
+    }
+    
+    #[test]
+    fn puts_bubbles_without_header_last() {
+        let conn = kv_db(&[
+            ("bubbleId:c1:b0", text(r#"{"type":1,"text":"draft"}"#)),
+            ("bubbleId:c1:b2", text(r#"{"type":2,"text":"answer"}"#)),
+            ("bubbleId:c1:b1", text(r#"{"type":1,"text":"question"}"#)),
+            ("bubbleId:c2:b9", text(r#"{"type":1,"text":"other chat"}"#)),
+            ("composerData:c1", text(r#"{"fullConversationHeadersOnly":[{"bubbleId":"b1"},{"bubbleId":"b2"}]}"#)),
+        ]);
+        
+        let transcript = load_transcript(&conn, CHAT).unwrap();
+        assert_eq!(bubble_ids(&transcript), ["b1", "b2", "b0"]);
+    }
+    
+    #[test]
+    fn reads_blob_values() {
+        let conn = kv_db(&[
+            ("bubbleId:c1:b1", Value::Blob(br#"{"type":1,"text":"stored as blob"}"#.to_vec())),
+            ("composerData:c1", Value::Blob(br#"{"name":"Blob chat"}"#.to_vec())),
+        ]);
+        
+        let transcript = load_transcript(&conn, CHAT).unwrap();
+        assert_eq!(transcript.name.as_deref(), Some("Blob chat"));
+        assert_eq!(transcript.messages.len(), 1);
+        assert_eq!(transcript.messages[0].text, "stored as blob");
+    }
+    
+    #[test]
+    fn missing_kv_table_gives_empty_transcript() {
+        let conn = Connection::open_in_memory().unwrap();
+        
+        let transcript = load_transcript(&conn, CHAT).unwrap();
+        assert_eq!(transcript.chat_id, CHAT);
+        assert!(transcript.name.is_none());
+        assert!(transcript.messages.is_empty());
+    }
+}
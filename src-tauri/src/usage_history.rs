@@ -0,0 +1,365 @@
+//! 长期统计：每次分析时把会话指标和当天的存储大小写入应用自己的数据库。
+//! Cursor 中的会话删除后，这里的记录只标记为已删除，累计数据不会随清理消失。
+
+use crate::chat_mode::ChatMode;
+use crate::{timezone, workspace_scan, AppError, StorageInfo, WorkspaceInfo};
+use chrono::Utc;
+use rusqlite::{params, Connection};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::path::PathBuf;
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ModeTotals {
+    pub kind: ChatMode,
+    pub label: String,
+    pub chat_count: i64,
+    pub lines_added: i64,
+    pub lines_removed: i64,
+    /// 归入这一模式的原始值及各自的会话数
+    pub raw_modes: BTreeMap<String, i64>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ProjectTotals {
+    /// 多项目工作区记为空字符串
+    pub project_path: String,
+    pub chat_count: i64,
+    pub deleted_chat_count: i64,
+    pub lines_added: i64,
+    pub lines_removed: i64,
+    pub files_changed: i64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct UsageTotals {
+    pub first_recorded_at: Option<String>,
+    pub last_recorded_at: Option<String>,
+    /// 记录过的全部会话，包括已删除的
+    pub chat_count: i64,
+    pub deleted_chat_count: i64,
+    pub lines_added: i64,
+    pub lines_removed: i64,
+    pub files_changed: i64,
+    /// 已删除会话贡献的行数
+    pub deleted_lines_added: i64,
+    pub deleted_lines_removed: i64,
+    pub avg_context_usage: f64,
+    pub modes: Vec<ModeTotals>,
+    /// 按新增行数降序
+    pub projects: Vec<ProjectTotals>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct DailyStorage {
+    /// 本地日期 YYYY-MM-DD，同一天多次记录时保留最后一次
+    pub day: String,
+    pub total_size: u64,
+    pub global_storage_size: u64,
+    pub workspace_storage_size: u64,
+    pub history_size: u64,
+    pub state_vscdb_size: u64,
+    /// 当时 Cursor 中仍存在的会话
+    pub chat_count: i64,
+    pub lines_added: i64,
+    pub lines_removed: i64,
+    pub recorded_at: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct RecordResult {
+    pub day: String,
+    pub chat_count: i64,
+    /// 第一次记录到的会话
+    pub new_chat_count: i64,
+    /// 本次发现已从 Cursor 中删除的会话
+    pub deleted_chat_count: i64,
+}
+
+/// 统计数据库放在本工具的数据目录下，编辑器重置 User 目录后累计数据仍然保留
+fn get_usage_db_path() -> Result<PathBuf, AppError> {
+    Ok(crate::get_app_data_path()?.join("usage.db"))
+}
+
+fn init_usage_db() -> Result<Connection, AppError> {
+    let conn = Connection::open(get_usage_db_path()?)?;
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS chat_metrics (
+            chat_id TEXT PRIMARY KEY,
+            workspace_id TEXT NOT NULL,
+            project_path TEXT NOT NULL,
+            name TEXT,
+            mode TEXT NOT NULL,
+            lines_added INTEGER NOT NULL DEFAULT 0,
+            lines_removed INTEGER NOT NULL DEFAULT 0,
+            files_changed INTEGER NOT NULL DEFAULT 0,
+            context_usage REAL NOT NULL DEFAULT 0,
+            created_at TEXT,
+            updated_at TEXT,
+            created_at_ms INTEGER,
+            updated_at_ms INTEGER,
+            first_seen TEXT NOT NULL,
+            last_seen TEXT NOT NULL,
+            deleted_at TEXT
+        );
+        CREATE TABLE IF NOT EXISTS daily_storage (
+            day TEXT PRIMARY KEY,
+            total_size INTEGER NOT NULL,
+            global_storage_size INTEGER NOT NULL,
+            workspace_storage_size INTEGER NOT NULL,
+            history_size INTEGER NOT NULL,
+            state_vscdb_size INTEGER NOT NULL,
+            chat_count INTEGER NOT NULL,
+            lines_added INTEGER NOT NULL,
+            lines_removed INTEGER NOT NULL,
+            recorded_at TEXT NOT NULL
+        );",
+    )?;
+    
+    // 早期版本没有毫秒时间戳列，需要补上
+    let has_created_at_ms = conn
+        .prepare("SELECT 1 FROM pragma_table_info('chat_metrics') WHERE name = 'created_at_ms'")?
+        .exists([])?;
+    if !has_created_at_ms {
+        conn.execute_batch(
+            "ALTER TABLE chat_metrics ADD COLUMN created_at_ms INTEGER;
+             ALTER TABLE chat_metrics ADD COLUMN updated_at_ms INTEGER;",
+        )?;
+    }
+    Ok(conn)
+}
+
+/// 记录一次分析结果：更新每个会话的最新指标，不再出现的会话标记为已删除，并写入当天的存储大小
+/// `unreadable` 中的工作区本次没能读取，它们的会话不会被标记为已删除
+pub(crate) fn record(
+    storage: &StorageInfo,
+    workspaces: &[WorkspaceInfo],
+    unreadable: &HashSet<String>,
+) -> Result<RecordResult, AppError> {
+    let mut conn = init_usage_db()?;
+    let now = Utc::now().format("%Y-%m-%d %H:%M:%S").to_string();
+    let day = timezone::current().today().format("%Y-%m-%d").to_string();
+    
+    let tx = conn.transaction()?;
+    // chat_id → (所在工作区, 是否已标记删除)
+    let known: HashMap<String, (String, bool)> = {
+        let mut stmt = tx.prepare("SELECT chat_id, workspace_id, deleted_at IS NOT NULL FROM chat_metrics")?;
+        let rows = stmt.query_map([], |row| Ok((row.get(0)?, (row.get(1)?, row.get(2)?))))?;
+        rows.collect::<Result<_, _>>()?
+    };
+    
+    let mut seen = HashSet::new();
+    let mut result = RecordResult {
+        day: day.clone(),
+        chat_count: 0,
+        new_chat_count: 0,
+        deleted_chat_count: 0,
+    };
+    let mut lines_added = 0i64;
+    let mut lines_removed = 0i64;
+    
+    for ws in workspaces {
+        let project_path = if ws.is_multi_project {
+            ""
+        } else {
+            ws.projects.first().map(|p| p.as_str()).unwrap_or("")
+        };
+        for chat in &ws.recent_chats {
+            // 同一会话可能出现在多个工作区库中，只记一次
+            if !seen.insert(chat.id.as_str()) {
+                continue;
+            }
+            if !known.contains_key(&chat.id) {
+                result.new_chat_count += 1;
+            }
+            lines_added += chat.lines_added;
+            lines_removed += chat.lines_removed;
+            
+            tx.execute(
+                "INSERT INTO chat_metrics (chat_id, workspace_id, project_path, name, mode, lines_added, lines_removed,
+                    files_changed, context_usage, created_at, updated_at, created_at_ms, updated_at_ms, first_seen, last_seen)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?14)
+                 ON CONFLICT(chat_id) DO UPDATE SET
+                    workspace_id = excluded.workspace_id,
+                    project_path = excluded.project_path,
+                    name = excluded.name,
+                    mode = excluded.mode,
+                    lines_added = excluded.lines_added,
+                    lines_removed = excluded.lines_removed,
+                    files_changed = excluded.files_changed,
+                    context_usage = excluded.context_usage,
+                    created_at = excluded.created_at,
+                    updated_at = excluded.updated_at,
+                    created_at_ms = excluded.created_at_ms,
+                    updated_at_ms = excluded.updated_at_ms,
+                    last_seen = excluded.last_seen,
+                    deleted_at = NULL",
+                params![
+                    chat.id,
+                    ws.id,
+                    project_path,
+                    chat.name,
+                    chat.mode,
+                    chat.lines_added,
+                    chat.lines_removed,
+                    chat.files_changed,
+                    chat.context_usage,
+                    chat.created_at,
+                    chat.updated_at,
+                    chat.created_at_ms,
+                    chat.updated_at_ms,
+                    now,
+                ],
+            )?;
+        }
+    }
+    result.chat_count = seen.len() as i64;
+    
+    for (chat_id, (workspace_id, deleted)) in &known {
+        if !deleted && !seen.contains(chat_id.as_str()) && !unreadable.contains(workspace_id) {
+            tx.execute("UPDATE chat_metrics SET deleted_at = ?1 WHERE chat_id = ?2", params![now, chat_id])?;
+            result.deleted_chat_count += 1;
+        }
+    }
+    
+    tx.execute(
+        "INSERT OR REPLACE INTO daily_storage (day, total_size, global_storage_size, workspace_storage_size,
+            history_size, state_vscdb_size, chat_count, lines_added, lines_removed, recorded_at)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
+        params![
+            day,
+            storage.total_size as i64,
+            storage.global_storage_size as i64,
+            storage.workspace_storage_size as i64,
+            storage.history_size as i64,
+            storage.state_vscdb_size as i64,
+            result.chat_count,
+            lines_added,
+            lines_removed,
+            now,
+        ],
+    )?;
+    tx.commit()?;
+    
+    Ok(result)
+}
+
+/// 立即扫描并记录一次，不需要加载完整的分析结果
+#[cfg_attr(feature = "gui", tauri::command)]
+pub fn record_usage_snapshot() -> Result<RecordResult, AppError> {
+    // get_storage_info 只会在找不到数据目录时失败
+    let storage = crate::get_storage_info().map_err(AppError::NotFound)?;
+    let snapshot = workspace_scan::scan()?;
+    record(&storage, &crate::workspaces_from_scan(&snapshot), &snapshot.unreadable_workspaces())
+}
+
+/// 所有记录过的会话的累计数据，包括已在 Cursor 中删除的
+#[cfg_attr(feature = "gui", tauri::command)]
+pub fn get_usage_totals() -> Result<UsageTotals, AppError> {
+    let conn = init_usage_db()?;
+    
+    let mut totals = conn.query_row(
+        "SELECT MIN(first_seen), MAX(last_seen), COUNT(*), COUNT(deleted_at),
+            COALESCE(SUM(lines_added), 0), COALESCE(SUM(lines_removed), 0), COALESCE(SUM(files_changed), 0),
+            COALESCE(SUM(CASE WHEN deleted_at IS NOT NULL THEN lines_added END), 0),
+            COALESCE(SUM(CASE WHEN deleted_at IS NOT NULL THEN lines_removed END), 0),
+            COALESCE(AVG(context_usage), 0)
+         FROM chat_metrics",
+        [],
+        |row| {
+            Ok(UsageTotals {
+                first_recorded_at: row.get(0)?,
+                last_recorded_at: row.get(1)?,
+                chat_count: row.get(2)?,
+                deleted_chat_count: row.get(3)?,
+                lines_added: row.get(4)?,
+                lines_removed: row.get(5)?,
+                files_changed: row.get(6)?,
+                deleted_lines_added: row.get(7)?,
+                deleted_lines_removed: row.get(8)?,
+                avg_context_usage: row.get(9)?,
+                modes: Vec::new(),
+                projects: Vec::new(),
+            })
+        },
+    )?;
+    
+    // 数据库中按原始值分组，再按归一化后的模式合并
+    let mut stmt = conn.prepare(
+        "SELECT mode, COUNT(*), SUM(lines_added), SUM(lines_removed) FROM chat_metrics GROUP BY mode",
+    )?;
+    let rows = stmt
+        .query_map([], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, i64>(1)?, row.get::<_, i64>(2)?, row.get::<_, i64>(3)?))
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+    let mut modes: BTreeMap<ChatMode, ModeTotals> = BTreeMap::new();
+    for (mode, chat_count, lines_added, lines_removed) in rows {
+        let kind = ChatMode::parse(&mode);
+        let totals = modes.entry(kind).or_insert_with(|| ModeTotals {
+            kind,
+            label: kind.label().to_string(),
+            chat_count: 0,
+            lines_added: 0,
+            lines_removed: 0,
+            raw_modes: BTreeMap::new(),
+        });
+        totals.chat_count += chat_count;
+        totals.lines_added += lines_added;
+        totals.lines_removed += lines_removed;
+        totals.raw_modes.insert(mode, chat_count);
+    }
+    totals.modes = modes.into_values().collect();
+    totals.modes.sort_by_key(|m| std::cmp::Reverse(m.chat_count));
+    
+    let mut stmt = conn.prepare(
+        "SELECT project_path, COUNT(*), COUNT(deleted_at), SUM(lines_added), SUM(lines_removed), SUM(files_changed)
+         FROM chat_metrics GROUP BY project_path ORDER BY SUM(lines_added) DESC, project_path",
+    )?;
+    totals.projects = stmt
+        .query_map([], |row| {
+            Ok(ProjectTotals {
+                project_path: row.get(0)?,
+                chat_count: row.get(1)?,
+                deleted_chat_count: row.get(2)?,
+                lines_added: row.get(3)?,
+                lines_removed: row.get(4)?,
+                files_changed: row.get(5)?,
+            })
+        })?
+        .collect::<Result<_, _>>()?;
+    
+    Ok(totals)
+}
+
+/// 每天的存储大小，按日期升序；指定 days 时只返回最近若干天
+#[cfg_attr(feature = "gui", tauri::command)]
+pub fn get_storage_trend(days: Option<u32>) -> Result<Vec<DailyStorage>, AppError> {
+    let conn = init_usage_db()?;
+    let since = days
+        .map(|d| (timezone::current().today() - chrono::Duration::days(i64::from(d.saturating_sub(1)))).format("%Y-%m-%d").to_string())
+        .unwrap_or_default();
+    
+    let mut stmt = conn.prepare(
+        "SELECT day, total_size, global_storage_size, workspace_storage_size, history_size, state_vscdb_size,
+            chat_count, lines_added, lines_removed, recorded_at
+         FROM daily_storage WHERE day >= ?1 ORDER BY day",
+    )?;
+    let trend = stmt
+        .query_map([since], |row| {
+            Ok(DailyStorage {
+                day: row.get(0)?,
+                total_size: row.get::<_, i64>(1)? as u64,
+                global_storage_size: row.get::<_, i64>(2)? as u64,
+                workspace_storage_size: row.get::<_, i64>(3)? as u64,
+                history_size: row.get::<_, i64>(4)? as u64,
+                state_vscdb_size: row.get::<_, i64>(5)? as u64,
+                chat_count: row.get(6)?,
+                lines_added: row.get(7)?,
+                lines_removed: row.get(8)?,
+                recorded_at: row.get(9)?,
+            })
+        })?
+        .collect::<Result<_, _>>()?;
+    Ok(trend)
+}
@@ -0,0 +1,283 @@
+//! 文件监听：workspaceStorage/*/state.vscdb 和 globalStorage/state.vscdb（含 WAL）变化后重新扫描，
+//! 借助扫描缓存只重新读取变化的工作区，再和上一次的结果对比，得出新增和更新的会话。
+//!
+//! 桌面应用把结果作为 Tauri 事件发给前端，命令行的 `watch` 直接输出。
+//! 无法监听或扫描失败时发出 `watcher-error` 事件，同样的错误只发一次。
+
+use crate::workspace_scan::{self, ScanSnapshot, WorkspaceScan};
+use crate::{editor, ChatSession};
+use notify::{Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+pub const CHAT_ADDED_EVENT: &str = "chat-added";
+pub const CHAT_UPDATED_EVENT: &str = "chat-updated";
+pub const STORAGE_CHANGED_EVENT: &str = "storage-changed";
+pub const WATCHER_ERROR_EVENT: &str = "watcher-error";
+
+/// Cursor 写入时会连续触发很多事件，等这段时间内的事件都到齐再扫描
+const DEBOUNCE: Duration = Duration::from_millis(500);
+/// 没有事件时，隔这么久检查一次数据目录或编辑器是否切换
+const ROOT_CHECK_INTERVAL: Duration = Duration::from_secs(5);
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ChatEvent {
+    pub workspace_id: String,
+    /// 单项目工作区的项目路径
+    pub project_path: Option<String>,
+    pub chat: ChatSession,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct RemovedChat {
+    pub workspace_id: String,
+    pub chat_id: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct StorageChange {
+    /// 数据库有变化的工作区（包括新出现的）
+    pub changed_workspaces: Vec<String>,
+    /// workspaceStorage 中已不存在的工作区
+    pub removed_workspaces: Vec<String>,
+    pub removed_chats: Vec<RemovedChat>,
+    /// 全局库有变化，会话的占用空间可能随之变化
+    pub global_changed: bool,
+}
+
+impl StorageChange {
+    fn is_empty(&self) -> bool {
+        self.changed_workspaces.is_empty() && self.removed_workspaces.is_empty() && !self.global_changed
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct WatcherError {
+    pub message: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(tag = "event", content = "payload", rename_all = "kebab-case")]
+pub enum WatchEvent {
+    ChatAdded(ChatEvent),
+    ChatUpdated(ChatEvent),
+    StorageChanged(StorageChange),
+    WatcherError(WatcherError),
+}
+
+impl WatchEvent {
+    pub fn name(&self) -> &'static str {
+        match self {
+            WatchEvent::ChatAdded(_) => CHAT_ADDED_EVENT,
+            WatchEvent::ChatUpdated(_) => CHAT_UPDATED_EVENT,
+            WatchEvent::StorageChanged(_) => STORAGE_CHANGED_EVENT,
+            WatchEvent::WatcherError(_) => WATCHER_ERROR_EVENT,
+        }
+    }
+}
+
+/// 对比两次扫描的结果；工作区目录没有变化且全局库也没变时直接跳过
+fn diff(previous: &ScanSnapshot, current: &ScanSnapshot) -> Vec<WatchEvent> {
+    let mut events = Vec::new();
+    let mut change = StorageChange {
+        global_changed: !current.same_global_sizes(previous),
+        ..Default::default()
+    };
+    
+    let before: HashMap<&str, &Arc<WorkspaceScan>> =
+        previous.workspaces.iter().map(|ws| (ws.id.as_str(), ws)).collect();
+    let mut seen = HashSet::new();
+    
+    for ws in &current.workspaces {
+        seen.insert(ws.id.as_str());
+        let old = before.get(ws.id.as_str());
+        let workspace_changed = old.is_none_or(|old| !Arc::ptr_eq(old, ws));
+        if !workspace_changed && !change.global_changed {
+            continue;
+        }
+        if workspace_changed {
+            change.changed_workspaces.push(ws.id.clone());
+        }
+        
+        let mut old_chats: HashMap<String, ChatSession> = old
+            .map(|old| previous.sessions(old))
+            .unwrap_or_default()
+            .into_iter()
+            .map(|chat| (chat.id.clone(), chat))
+            .collect();
+        for chat in current.sessions(ws) {
+            let event = |chat| ChatEvent {
+                workspace_id: ws.id.clone(),
+                project_path: ws.folder.clone(),
+                chat,
+            };
+            match old_chats.remove(&chat.id) {
+                None => events.push(WatchEvent::ChatAdded(event(chat))),
+                Some(old) if old != chat => events.push(WatchEvent::ChatUpdated(event(chat))),
+                Some(_) => {}
+            }
+        }
+        change.removed_chats.extend(old_chats.into_keys().map(|chat_id| RemovedChat {
+            workspace_id: ws.id.clone(),
+            chat_id,
+        }));
+    }
+    
+    for ws in &previous.workspaces {
+        if seen.contains(ws.id.as_str()) {
+            continue;
+        }
+        change.removed_workspaces.push(ws.id.clone());
+        change.removed_chats.extend(ws.sessions.iter().map(|chat| RemovedChat {
+            workspace_id: ws.id.clone(),
+            chat_id: chat.id.clone(),
+        }));
+    }
+    
+    if !change.is_empty() {
+        events.push(WatchEvent::StorageChanged(change));
+    }
+    events
+}
+
+/// 本次没能读取的工作区沿用上一次的结果，避免数据库被短暂占用时误报会话被删除
+fn keep_unreadable(previous: &ScanSnapshot, current: &mut ScanSnapshot) {
+    for ws in current.workspaces.iter_mut().filter(|ws| ws.load_error.is_some()) {
+        if let Some(old) = previous.workspaces.iter().find(|old| old.id == ws.id) {
+            *ws = old.clone();
+        }
+    }
+}
+
+/// 只关心数据库、WAL、workspace.json 的变化，以及工作区目录本身的增删；
+/// 读取数据库时产生的访问事件忽略，否则扫描会触发下一次扫描
+fn is_relevant(event: &Event, workspace_storage: &Path) -> bool {
+    if matches!(event.kind, EventKind::Access(_)) {
+        return false;
+    }
+    event.paths.iter().any(|path| {
+        path.parent() == Some(workspace_storage)
+            || path
+                .file_name()
+                .is_some_and(|name| name == "state.vscdb" || name == "state.vscdb-wal" || name == "workspace.json")
+    })
+}
+
+fn start_watcher(user_path: &Path, tx: mpsc::Sender<Event>) -> notify::Result<RecommendedWatcher> {
+    let mut watcher = notify::recommended_watcher(move |res: notify::Result<Event>| {
+        if let Ok(event) = res {
+            let _ = tx.send(event);
+        }
+    })?;
+    watcher.watch(&user_path.join("workspaceStorage"), RecursiveMode::Recursive)?;
+    watcher.watch(&user_path.join("globalStorage"), RecursiveMode::NonRecursive)?;
+    Ok(watcher)
+}
+
+/// 发出错误事件，和上一次相同的错误不再重复发送
+fn report_error<E>(
+    emit: &mut impl FnMut(WatchEvent) -> Result<(), E>,
+    last_error: &mut Option<String>,
+    message: String,
+) -> Result<(), E> {
+    if last_error.as_ref() == Some(&message) {
+        return Ok(());
+    }
+    *last_error = Some(message.clone());
+    emit(WatchEvent::WatcherError(WatcherError { message }))
+}
+
+/// 监听当前数据目录并把变化交给 `emit`，`emit` 返回错误时停止监听并返回该错误。
+/// 数据目录或编辑器切换后重新开始监听，切换本身不产生事件
+pub fn watch<E>(mut emit: impl FnMut(WatchEvent) -> Result<(), E>) -> Result<(), E> {
+    let (tx, rx) = mpsc::channel();
+    let mut target: Option<(PathBuf, &'static str)> = None;
+    let mut watcher: Option<RecommendedWatcher> = None;
+    let mut baseline: Option<ScanSnapshot> = None;
+    let mut last_error: Option<String> = None;
+    
+    loop {
+        let current_target = match crate::get_cursor_user_path() {
+            Ok(path) => Some((path, editor::active().id)),
+            Err(e) => {
+                report_error(&mut emit, &mut last_error, format!("无法定位数据目录: {}", e))?;
+                None
+            }
+        };
+        if current_target != target {
+            watcher = None;
+            baseline = None;
+            if let Some((user_path, _)) = &current_target {
+                match start_watcher(user_path, tx.clone()) {
+                    Ok(w) => {
+                        watcher = Some(w);
+                        last_error = None;
+                    }
+                    Err(e) => report_error(&mut emit, &mut last_error, format!("无法监听 {}: {}", user_path.display(), e))?,
+                }
+            }
+            target = current_target;
+        }
+        let Some((user_path, _)) = target.as_ref().filter(|_| watcher.is_some()) else {
+            // 监听失败时稍后重试
+            thread::sleep(ROOT_CHECK_INTERVAL);
+            target = None;
+            continue;
+        };
+        let workspace_storage = user_path.join("workspaceStorage");
+        
+        // 还没有基准（开始监听后的首次扫描失败）时每轮重试，失败原因作为错误事件发出
+        if baseline.is_none() {
+            match workspace_scan::scan() {
+                Ok(snapshot) => {
+                    baseline = Some(snapshot);
+                    last_error = None;
+                }
+                Err(e) => report_error(&mut emit, &mut last_error, format!("扫描 {} 失败: {}", user_path.display(), e))?,
+            }
+        }
+        
+        match rx.recv_timeout(ROOT_CHECK_INTERVAL) {
+            Ok(event) if is_relevant(&event, &workspace_storage) => {}
+            Ok(_) | Err(RecvTimeoutError::Timeout) => continue,
+            Err(RecvTimeoutError::Disconnected) => return Ok(()),
+        }
+        thread::sleep(DEBOUNCE);
+        while rx.try_recv().is_ok() {}
+        
+        let mut current = match workspace_scan::scan() {
+            Ok(current) => current,
+            Err(e) => {
+                report_error(&mut emit, &mut last_error, format!("扫描 {} 失败: {}", user_path.display(), e))?;
+                continue;
+            }
+        };
+        last_error = None;
+        if let Some(previous) = &baseline {
+            keep_unreadable(previous, &mut current);
+            for event in diff(previous, &current) {
+                emit(event)?;
+            }
+        }
+        baseline = Some(current);
+    }
+}
+
+/// 在后台线程监听，变化以 Tauri 事件发给前端；事件发不出去（应用正在退出）时线程结束
+#[cfg(feature = "gui")]
+pub fn spawn(app: tauri::AppHandle) {
+    use tauri::Emitter;
+    
+    thread::spawn(move || {
+        watch(|event| match &event {
+            WatchEvent::ChatAdded(payload) | WatchEvent::ChatUpdated(payload) => app.emit(event.name(), payload),
+            WatchEvent::StorageChanged(payload) => app.emit(event.name(), payload),
+            WatchEvent::WatcherError(payload) => app.emit(event.name(), payload),
+        })
+    });
+}
@@ -0,0 +1,234 @@
+//! 孤立的 workspaceStorage 目录：项目文件夹或 `.code-workspace` 文件已不存在，
+//! 或者 workspace.json 缺失、无法解析。这些目录不会出现在项目和工作区列表中，但仍然占用空间。
+//!
+//! 清理前会重新检查每个目录，归档时整个目录移动到应用自己的归档区，可以手动放回 workspaceStorage。
+
+use crate::workspace_uri::{self, WorkspaceLocation};
+use crate::{cursor_guard, AppError};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum OrphanReason {
+    /// 单项目工作区的文件夹已不存在
+    FolderMissing,
+    /// 多项目工作区的 `.code-workspace` 文件已不存在
+    WorkspaceFileMissing,
+    /// 没有 workspace.json
+    WorkspaceJsonMissing,
+    /// workspace.json 无法解析或缺少 folder/workspace 字段
+    WorkspaceJsonInvalid,
+}
+
+impl OrphanReason {
+    pub fn label(&self) -> &'static str {
+        match self {
+            OrphanReason::FolderMissing => "文件夹已删除",
+            OrphanReason::WorkspaceFileMissing => "工作区文件已删除",
+            OrphanReason::WorkspaceJsonMissing => "缺少 workspace.json",
+            OrphanReason::WorkspaceJsonInvalid => "workspace.json 无效",
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct OrphanWorkspace {
+    /// workspaceStorage 下的目录名（哈希）
+    pub id: String,
+    pub reason: OrphanReason,
+    /// workspace.json 中记录的文件夹或工作区文件路径
+    pub target: Option<String>,
+    pub size: u64,
+    pub size_human: String,
+    /// state.vscdb 中仍保存的会话数量
+    pub chat_count: i64,
+    pub last_modified: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct OrphanReport {
+    pub total_size: u64,
+    pub total_size_human: String,
+    pub workspaces: Vec<OrphanWorkspace>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum OrphanAction {
+    /// 移动到归档区
+    Archive,
+    /// 直接删除
+    Delete,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct OrphanSkip {
+    pub id: String,
+    pub error: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct OrphanRemovalResult {
+    pub action: OrphanAction,
+    pub removed: Vec<String>,
+    /// 不再是孤立目录或处理失败的目录
+    pub skipped: Vec<OrphanSkip>,
+    pub bytes_freed: u64,
+    pub bytes_freed_human: String,
+    /// 本次归档的目录
+    pub archive_dir: Option<String>,
+}
+
+fn workspace_storage_dir() -> Result<PathBuf, AppError> {
+    Ok(crate::get_cursor_user_path()?.join("workspaceStorage"))
+}
+
+/// 归档区放在本工具的数据目录下，不占用编辑器的 User 目录
+fn workspace_archive_dir() -> Result<PathBuf, AppError> {
+    Ok(crate::get_app_data_path()?.join("workspace-archive"))
+}
+
+/// 判断一个 workspaceStorage 目录是否孤立，返回原因和记录的路径；远程工作区无法检查，视为正常
+fn check_orphan(ws_path: &Path) -> Option<(OrphanReason, Option<String>)> {
+    let Ok(content) = fs::read_to_string(ws_path.join("workspace.json")) else {
+        return Some((OrphanReason::WorkspaceJsonMissing, None));
+    };
+    let Ok(data) = serde_json::from_str::<serde_json::Value>(&content) else {
+        return Some((OrphanReason::WorkspaceJsonInvalid, None));
+    };
+    
+    let (uri, reason) = if let Some(folder) = data.get("folder").and_then(|v| v.as_str()) {
+        (folder, OrphanReason::FolderMissing)
+    } else if let Some(workspace) = data.get("workspace").and_then(|v| v.as_str()) {
+        (workspace, OrphanReason::WorkspaceFileMissing)
+    } else {
+        return Some((OrphanReason::WorkspaceJsonInvalid, None));
+    };
+    
+    if workspace_uri::parse_location(uri) != WorkspaceLocation::Local {
+        return None;
+    }
+    let path = workspace_uri::uri_to_path(uri);
+    if Path::new(&path).exists() {
+        return None;
+    }
+    Some((reason, Some(path)))
+}
+
+fn last_modified(ws_path: &Path) -> Option<String> {
+    let db = ws_path.join("state.vscdb");
+    let path = if db.exists() { db } else { ws_path.to_path_buf() };
+    let modified = fs::metadata(path).and_then(|m| m.modified()).ok()?;
+    crate::timestamp_to_string(DateTime::<Utc>::from(modified).timestamp_millis())
+}
+
+#[cfg_attr(feature = "gui", tauri::command)]
+pub fn scan_orphaned_workspaces() -> Result<OrphanReport, AppError> {
+    let workspace_storage = workspace_storage_dir()?;
+    let mut workspaces = Vec::new();
+    
+    if let Ok(entries) = fs::read_dir(&workspace_storage) {
+        for entry in entries.filter_map(|e| e.ok()) {
+            let ws_path = entry.path();
+            if !ws_path.is_dir() {
+                continue;
+            }
+            let Some((reason, target)) = check_orphan(&ws_path) else {
+                continue;
+            };
+            
+            let db_path = ws_path.join("state.vscdb");
+            let chat_count = if db_path.exists() {
+                crate::load_workspace_sessions(&db_path).len() as i64
+            } else {
+                0
+            };
+            let size = crate::get_dir_size(&ws_path);
+            
+            workspaces.push(OrphanWorkspace {
+                id: entry.file_name().to_string_lossy().to_string(),
+                reason,
+                target,
+                size,
+                size_human: crate::format_size(size),
+                chat_count,
+                last_modified: last_modified(&ws_path),
+            });
+        }
+    }
+    
+    workspaces.sort_by_key(|w| std::cmp::Reverse(w.size));
+    let total_size = workspaces.iter().map(|w| w.size).sum();
+    
+    Ok(OrphanReport {
+        total_size,
+        total_size_human: crate::format_size(total_size),
+        workspaces,
+    })
+}
+
+/// 归档或删除孤立的 workspaceStorage 目录，处理前重新检查，已恢复正常的目录会跳过
+#[cfg_attr(feature = "gui", tauri::command)]
+pub fn remove_orphaned_workspaces(
+    workspace_ids: Vec<String>,
+    action: OrphanAction,
+) -> Result<OrphanRemovalResult, AppError> {
+    cursor_guard::ensure_not_running()?;
+    for id in &workspace_ids {
+        crate::validate_workspace_id(id)?;
+    }
+    
+    let workspace_storage = workspace_storage_dir()?;
+    let archive = match action {
+        OrphanAction::Archive => Some(workspace_archive_dir()?.join(Utc::now().format("%Y%m%d%H%M%S%3f").to_string())),
+        OrphanAction::Delete => None,
+    };
+    
+    let mut result = OrphanRemovalResult {
+        action,
+        removed: Vec::new(),
+        skipped: Vec::new(),
+        bytes_freed: 0,
+        bytes_freed_human: String::new(),
+        archive_dir: None,
+    };
+    
+    for id in workspace_ids {
+        let ws_path = workspace_storage.join(&id);
+        let skip = |error: String| OrphanSkip { id: id.clone(), error };
+        if !ws_path.is_dir() {
+            result.skipped.push(skip("目录不存在".to_string()));
+            continue;
+        }
+        if check_orphan(&ws_path).is_none() {
+            result.skipped.push(skip("工作区仍然存在".to_string()));
+            continue;
+        }
+        if cursor_guard::is_db_locked(&ws_path.join("state.vscdb")) {
+            result.skipped.push(skip("数据库被占用".to_string()));
+            continue;
+        }
+        
+        let size = crate::get_dir_size(&ws_path);
+        let outcome = match &archive {
+            Some(archive) => fs::create_dir_all(archive).and_then(|_| crate::move_path(&ws_path, &archive.join(&id))),
+            None => fs::remove_dir_all(&ws_path),
+        };
+        match outcome {
+            Ok(()) => {
+                result.bytes_freed += size;
+                result.removed.push(id);
+            }
+            Err(e) => result.skipped.push(skip(e.to_string())),
+        }
+    }
+    
+    result.bytes_freed_human = crate::format_size(result.bytes_freed);
+    result.archive_dir = archive
+        .filter(|dir| dir.exists())
+        .map(|dir| dir.to_string_lossy().to_string());
+    Ok(result)
+}
@@ -0,0 +1,263 @@
+//! workspaceStorage 扫描：每个工作区目录只读取一次，多个目录并行读取，
+//! 结果按 workspace.json、state.vscdb（含 WAL）的修改时间和大小缓存。
+//! 项目列表、工作区列表和概览都从同一份扫描快照派生，不再各自遍历一遍。
+
+use crate::timezone::{self, Zone};
+use crate::workspace_uri::{self, WorkspaceLocation};
+use crate::{editor, AppError, ChatSession};
+use rayon::prelude::*;
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::SystemTime;
+
+/// 文件的修改时间和大小，任一变化都重新读取
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct FileStamp {
+    modified: Option<SystemTime>,
+    len: u64,
+}
+
+fn stamp(path: &Path) -> Option<FileStamp> {
+    let meta = fs::metadata(path).ok()?;
+    Some(FileStamp {
+        modified: meta.modified().ok(),
+        len: meta.len(),
+    })
+}
+
+fn wal_path(db_path: &Path) -> PathBuf {
+    let mut wal = db_path.as_os_str().to_owned();
+    wal.push("-wal");
+    PathBuf::from(wal)
+}
+
+/// 判断缓存是否还有效的依据；会话所在的键由编辑器决定，所以编辑器也算在内，
+/// 缓存中的时间文字按时区格式化，时区同理
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Fingerprint {
+    editor: &'static str,
+    zone: Zone,
+    workspace_json: Option<FileStamp>,
+    db: Option<FileStamp>,
+    wal: Option<FileStamp>,
+    /// 多项目工作区的 .code-workspace 文件
+    workspace_file: Option<FileStamp>,
+}
+
+impl Fingerprint {
+    fn new(editor: &'static str, zone: Zone, ws_path: &Path, workspace_file: Option<&Path>) -> Self {
+        let db = ws_path.join("state.vscdb");
+        Fingerprint {
+            editor,
+            zone,
+            workspace_json: stamp(&ws_path.join("workspace.json")),
+            wal: stamp(&wal_path(&db)),
+            db: stamp(&db),
+            workspace_file: workspace_file.and_then(stamp),
+        }
+    }
+}
+
+/// 一个 workspaceStorage 目录的读取结果
+#[derive(Debug)]
+pub(crate) struct WorkspaceScan {
+    pub id: String,
+    /// 单项目工作区的项目路径
+    pub folder: Option<String>,
+    /// 多项目工作区的 .code-workspace 文件
+    pub workspace_file: Option<PathBuf>,
+    pub projects: Vec<String>,
+    pub location: WorkspaceLocation,
+    pub created_at: String,
+    /// 会话列表，storage_size 只包含工作区库中的数据
+    pub sessions: Vec<ChatSession>,
+    /// 数据库读取失败（如被占用）时的原因，此时 sessions 为空但不代表会话已被删除
+    pub load_error: Option<String>,
+}
+
+struct CacheEntry {
+    fingerprint: Fingerprint,
+    scan: Arc<WorkspaceScan>,
+}
+
+/// 按 workspaceStorage 子目录缓存的扫描结果
+static CACHE: Mutex<Option<HashMap<PathBuf, CacheEntry>>> = Mutex::new(None);
+
+type GlobalSizes = Arc<HashMap<String, u64>>;
+
+/// 全局库中各会话的占用空间，按全局库的修改时间和大小缓存
+struct GlobalCacheEntry {
+    db_path: PathBuf,
+    db: Option<FileStamp>,
+    wal: Option<FileStamp>,
+    sizes: GlobalSizes,
+}
+
+static GLOBAL_CACHE: Mutex<Option<GlobalCacheEntry>> = Mutex::new(None);
+
+/// 一次扫描的结果
+pub(crate) struct ScanSnapshot {
+    /// 按目录名排序
+    pub workspaces: Vec<Arc<WorkspaceScan>>,
+    global_sizes: GlobalSizes,
+}
+
+impl ScanSnapshot {
+    /// 工作区的会话，storage_size 补上全局库中的部分
+    pub fn sessions(&self, workspace: &WorkspaceScan) -> Vec<ChatSession> {
+        let mut sessions = workspace.sessions.clone();
+        crate::add_storage_sizes(&mut sessions, &self.global_sizes);
+        sessions
+    }
+    
+    /// 本次没能读取数据库的工作区
+    pub fn unreadable_workspaces(&self) -> HashSet<String> {
+        self.workspaces
+            .iter()
+            .filter(|ws| ws.load_error.is_some())
+            .map(|ws| ws.id.clone())
+            .collect()
+    }
+    
+    /// 两次扫描之间全局库是否没有变化
+    pub fn same_global_sizes(&self, other: &ScanSnapshot) -> bool {
+        Arc::ptr_eq(&self.global_sizes, &other.global_sizes)
+    }
+    
+    /// 两次扫描之间工作区和全局库是否都没有变化
+    pub fn unchanged_since(&self, other: &ScanSnapshot) -> bool {
+        self.same_global_sizes(other)
+            && self.workspaces.len() == other.workspaces.len()
+            && self.workspaces.iter().zip(&other.workspaces).all(|(a, b)| Arc::ptr_eq(a, b))
+    }
+}
+
+fn read_workspace(ws_path: &Path, chat_keys: &[&str]) -> WorkspaceScan {
+    let mut scan = WorkspaceScan {
+        id: ws_path.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default(),
+        folder: None,
+        workspace_file: None,
+        projects: Vec::new(),
+        location: WorkspaceLocation::Local,
+        created_at: String::new(),
+        sessions: Vec::new(),
+        load_error: None,
+    };
+    
+    if let Some(data) = fs::read_to_string(ws_path.join("workspace.json"))
+        .ok()
+        .and_then(|content| serde_json::from_str::<serde_json::Value>(&content).ok())
+    {
+        if let Some(folder) = data.get("folder").and_then(|v| v.as_str()) {
+            let path = workspace_uri::uri_to_path(folder);
+            scan.projects.push(path.clone());
+            scan.folder = Some(path);
+            scan.location = workspace_uri::parse_location(folder);
+        } else if let Some(workspace) = data.get("workspace").and_then(|v| v.as_str()) {
+            scan.location = workspace_uri::parse_location(workspace);
+            let ws_file_path = PathBuf::from(workspace_uri::uri_to_path(workspace));
+            
+            // 未命名工作区的路径中带有创建时间戳
+            let ts = ws_file_path
+                .iter()
+                .map(|s| s.to_string_lossy())
+                .find(|s| s.chars().all(|c| c.is_numeric()) && s.len() > 10)
+                .and_then(|s| s.parse::<i64>().ok());
+            if let Some(ts) = ts {
+                scan.created_at = crate::timestamp_to_string(ts).unwrap_or_default();
+            }
+            
+            scan.projects = crate::get_workspace_projects(&ws_file_path);
+            scan.workspace_file = Some(ws_file_path);
+        }
+    }
+    
+    let db_path = ws_path.join("state.vscdb");
+    if db_path.exists() {
+        match crate::load_sessions_with_keys(&db_path, chat_keys) {
+            Ok(sessions) => scan.sessions = sessions,
+            Err(e) => scan.load_error = Some(e.to_string()),
+        }
+    }
+    scan
+}
+
+fn global_sizes() -> Result<GlobalSizes, AppError> {
+    let db_path = crate::get_global_db_path()?;
+    let db = stamp(&db_path);
+    let wal = stamp(&wal_path(&db_path));
+    
+    let mut cache = GLOBAL_CACHE.lock().unwrap_or_else(|e| e.into_inner());
+    if let Some(entry) = cache.as_ref() {
+        if entry.db_path == db_path && entry.db == db && entry.wal == wal {
+            return Ok(entry.sizes.clone());
+        }
+    }
+    
+    let sizes = Arc::new(crate::load_global_chat_storage_sizes()?);
+    *cache = Some(GlobalCacheEntry { db_path, db, wal, sizes: sizes.clone() });
+    Ok(sizes)
+}
+
+/// 扫描当前数据目录下的所有工作区，未变化的目录直接使用缓存
+pub(crate) fn scan() -> Result<ScanSnapshot, AppError> {
+    // 数据目录和编辑器可能是线程内临时切换的，在进入线程池之前取好
+    let workspace_storage = crate::get_cursor_user_path()?.join("workspaceStorage");
+    let profile = editor::active();
+    let zone = timezone::current();
+    let global_sizes = global_sizes()?;
+    
+    let dirs: Vec<PathBuf> = fs::read_dir(&workspace_storage)
+        .map(|entries| {
+            entries
+                .filter_map(|e| e.ok())
+                .map(|e| e.path())
+                .filter(|p| p.is_dir())
+                .collect()
+        })
+        .unwrap_or_default();
+    
+    let cached: HashMap<PathBuf, (Fingerprint, Arc<WorkspaceScan>)> = {
+        let cache = CACHE.lock().unwrap_or_else(|e| e.into_inner());
+        cache
+            .iter()
+            .flatten()
+            .filter(|(path, _)| path.starts_with(&workspace_storage))
+            .map(|(path, entry)| (path.clone(), (entry.fingerprint.clone(), entry.scan.clone())))
+            .collect()
+    };
+    
+    let results: Vec<(PathBuf, CacheEntry)> = dirs
+        .into_par_iter()
+        .map(|ws_path| {
+            if let Some((fingerprint, scan)) = cached.get(&ws_path) {
+                let current = Fingerprint::new(profile.id, zone, &ws_path, scan.workspace_file.as_deref());
+                if current == *fingerprint {
+                    return (ws_path, CacheEntry { fingerprint: current, scan: scan.clone() });
+                }
+            }
+            // 先取指纹再读取，读取过程中文件变化时下次扫描会重新读取
+            let before = Fingerprint::new(profile.id, zone, &ws_path, None);
+            let scan = read_workspace(&ws_path, profile.chat_keys);
+            let fingerprint = Fingerprint {
+                workspace_file: scan.workspace_file.as_deref().and_then(stamp),
+                ..before
+            };
+            (ws_path, CacheEntry { fingerprint, scan: Arc::new(scan) })
+        })
+        .collect();
+    
+    let mut workspaces: Vec<Arc<WorkspaceScan>> = results.iter().map(|(_, entry)| entry.scan.clone()).collect();
+    workspaces.sort_by(|a, b| a.id.cmp(&b.id));
+    
+    // 同一数据目录下的旧条目整体替换，已删除的目录随之清除
+    let mut cache = CACHE.lock().unwrap_or_else(|e| e.into_inner());
+    let cache = cache.get_or_insert_with(HashMap::new);
+    cache.retain(|path, _| !path.starts_with(&workspace_storage));
+    // 读取失败的结果不缓存，下次扫描重新读取
+    cache.extend(results.into_iter().filter(|(_, entry)| entry.scan.load_error.is_none()));
+    
+    Ok(ScanSnapshot { workspaces, global_sizes })
+}
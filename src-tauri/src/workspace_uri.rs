@@ -0,0 +1,282 @@
+//! workspace.json 中的 URI 解析：`file://` URI 完整解码后转换为本机路径
+//!
+//! - `file:///Users/me/%E9%A1%B9%E7%9B%AE` → `/Users/me/项目`
+//! - `file:///c%3A/work/app` → `c:\work\app`（Windows）
+//! - `file://server/share/app` → `\\server\share\app`（Windows UNC）
+//!
+//! 远程工作区（`vscode-remote://ssh-remote+host/...` 等）保留解码后的完整 URI 作为项目路径，
+//! 这样不同主机上的同名目录不会合并，同时解析出类型化的 [`WorkspaceLocation`]。
+
+use percent_encoding::percent_decode_str;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+const FILE_SCHEME: &str = "file://";
+const REMOTE_SCHEME: &str = "vscode-remote://";
+/// 多项目工作区作为一个项目显示时，各项目路径用它连接
+pub const PROJECT_LIST_SEPARATOR: &str = " + ";
+
+/// 工作区所在位置
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum WorkspaceLocation {
+    Local,
+    Ssh { host: String },
+    Wsl { distro: String },
+    DevContainer { name: String },
+    Codespaces { name: String },
+    /// 其他远程类型（如 tunnel），保留原始 authority
+    Remote { authority: String },
+}
+
+impl WorkspaceLocation {
+    pub fn label(&self) -> String {
+        match self {
+            WorkspaceLocation::Local => "本地".to_string(),
+            WorkspaceLocation::Ssh { host } => format!("SSH: {}", host),
+            WorkspaceLocation::Wsl { distro } => format!("WSL: {}", distro),
+            WorkspaceLocation::DevContainer { name } => format!("Dev Container: {}", name),
+            WorkspaceLocation::Codespaces { name } => format!("Codespaces: {}", name),
+            WorkspaceLocation::Remote { authority } => format!("远程: {}", authority),
+        }
+    }
+}
+
+/// 部分 authority 是十六进制编码的 JSON，例如 `dev-container+7b22...7d`
+fn decode_hex_json(s: &str) -> Option<serde_json::Value> {
+    if s.is_empty() || !s.len().is_multiple_of(2) || !s.bytes().all(|b| b.is_ascii_hexdigit()) {
+        return None;
+    }
+    let bytes: Vec<u8> = (0..s.len())
+        .step_by(2)
+        .filter_map(|i| u8::from_str_radix(&s[i..i + 2], 16).ok())
+        .collect();
+    serde_json::from_slice(&bytes).ok()
+}
+
+/// 从十六进制 JSON 中依次尝试读取字段，都没有时返回原始值
+fn hex_json_field(value: &str, fields: &[&str]) -> String {
+    decode_hex_json(value)
+        .and_then(|json| {
+            fields
+                .iter()
+                .find_map(|f| json.get(*f).and_then(|v| v.as_str()).map(|s| s.to_string()))
+        })
+        .unwrap_or_else(|| value.to_string())
+}
+
+/// 解析 URI 对应的工作区位置，`file://` 和无法识别的 scheme 视为本地
+pub fn parse_location(uri: &str) -> WorkspaceLocation {
+    let Some(rest) = uri.strip_prefix(REMOTE_SCHEME) else {
+        return WorkspaceLocation::Local;
+    };
+    let authority = percent_decode(rest.split('/').next().unwrap_or(""));
+    let (kind, value) = authority.split_once('+').unwrap_or((authority.as_str(), ""));
+    
+    match kind {
+        "ssh-remote" => WorkspaceLocation::Ssh { host: hex_json_field(value, &["hostName"]) },
+        "wsl" => WorkspaceLocation::Wsl { distro: value.to_string() },
+        "dev-container" | "attached-container" => {
+            let name = hex_json_field(value, &["containerName", "hostPath"]);
+            WorkspaceLocation::DevContainer { name: path_name(&name) }
+        }
+        "codespaces" => WorkspaceLocation::Codespaces { name: value.to_string() },
+        _ => WorkspaceLocation::Remote { authority: authority.clone() },
+    }
+}
+
+/// 百分号解码，非 UTF-8 字节按替换字符处理
+pub fn percent_decode(s: &str) -> String {
+    percent_decode_str(s).decode_utf8_lossy().to_string()
+}
+
+/// `/c:/work` 形式的 Windows 盘符路径
+fn has_drive_letter(path: &str) -> bool {
+    let bytes = path.as_bytes();
+    bytes.len() >= 3 && bytes[0] == b'/' && bytes[1].is_ascii_alphabetic() && bytes[2] == b':'
+}
+
+/// 将 URI 转换为本机路径；不是 `file://` 的 URI（远程工作区）原样解码返回
+pub fn uri_to_path(uri: &str) -> String {
+    let Some(rest) = uri.strip_prefix(FILE_SCHEME) else {
+        return percent_decode(uri);
+    };
+    
+    // file://<authority>/<path>，authority 为空表示本机
+    let (authority, path) = match rest.find('/') {
+        Some(i) => (&rest[..i], &rest[i..]),
+        None => (rest, "/"),
+    };
+    let authority = percent_decode(authority);
+    let path = percent_decode(path);
+    
+    if !authority.is_empty() && authority != "localhost" {
+        // UNC 路径
+        let unc = format!("//{}{}", authority, path);
+        return if cfg!(windows) { unc.replace('/', "\\") } else { unc };
+    }
+    
+    if has_drive_letter(&path) {
+        let drive = &path[1..];
+        return if cfg!(windows) { drive.replace('/', "\\") } else { drive.to_string() };
+    }
+    
+    if cfg!(windows) {
+        path.replace('/', "\\")
+    } else {
+        path
+    }
+}
+
+/// 多项目工作区文件中的文件夹：`uri` 为 URI，`path` 为绝对路径或相对工作区文件的路径
+pub fn folder_entry_to_path(entry: &serde_json::Value, workspace_file: &Path) -> Option<String> {
+    if let Some(uri) = entry.get("uri").and_then(|v| v.as_str()) {
+        return Some(uri_to_path(uri));
+    }
+    let path = entry.get("path").and_then(|v| v.as_str())?;
+    let path = PathBuf::from(path);
+    if path.is_absolute() {
+        return Some(path.to_string_lossy().to_string());
+    }
+    let base = workspace_file.parent().unwrap_or(Path::new(""));
+    Some(base.join(path).to_string_lossy().to_string())
+}
+
+/// 路径的比较键：Windows 下忽略大小写和分隔符差异，并去掉末尾的分隔符
+pub fn path_key(path: &str) -> String {
+    if cfg!(windows) {
+        path.replace('/', "\\").trim_end_matches('\\').to_lowercase()
+    } else {
+        path.trim_end_matches('/').to_string()
+    }
+}
+
+/// 比较两个路径是否指向同一位置
+pub fn same_path(a: &str, b: &str) -> bool {
+    path_key(a) == path_key(b)
+}
+
+/// `a + b` 形式的项目列表是否正好是工作区的项目（顺序一致）
+pub fn same_project_list(joined: &str, projects: &[String]) -> bool {
+    let parts: Vec<&str> = joined.split(PROJECT_LIST_SEPARATOR).collect();
+    parts.len() == projects.len() && parts.iter().zip(projects).all(|(a, b)| same_path(a, b))
+}
+
+/// 路径的最后一段作为项目名称
+pub fn path_name(path: &str) -> String {
+    Path::new(path)
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_else(|| path.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    /// 期望值按 Unix 写法给出，Windows 下换成反斜杠
+    fn native(path: &str) -> String {
+        if cfg!(windows) {
+            path.replace('/', "\\")
+        } else {
+            path.to_string()
+        }
+    }
+    
+    #[test]
+    fn uri_to_path_decodes_file_uris() {
+        let cases = [
+            ("file:///Users/me/app", "/Users/me/app"),
+            ("file:///Users/me/%E9%A1%B9%E7%9B%AE", "/Users/me/项目"),
+            ("file:///Users/me/my%20app", "/Users/me/my app"),
+            ("file://localhost/srv/app", "/srv/app"),
+            ("file:///c%3A/work/app", "c:/work/app"),
+            ("file:///D:/work/app", "D:/work/app"),
+            ("file://server/share/app", "//server/share/app"),
+            ("file://server", "//server/"),
+        ];
+        for (uri, expected) in cases {
+            assert_eq!(uri_to_path(uri), native(expected), "{}", uri);
+        }
+    }
+    
+    #[test]
+    fn uri_to_path_keeps_remote_uris() {
+        let cases = [
+            ("vscode-remote://ssh-remote%2Bbuild-box/home/me/app", "vscode-remote://ssh-remote+build-box/home/me/app"),
+            ("vscode-remote://wsl+Ubuntu/home/me/app", "vscode-remote://wsl+Ubuntu/home/me/app"),
+        ];
+        for (uri, expected) in cases {
+            assert_eq!(uri_to_path(uri), expected, "{}", uri);
+        }
+    }
+    
+    #[test]
+    fn percent_decode_replaces_invalid_utf8() {
+        let cases = [("a%20b", "a b"), ("%E4%B8%AD", "中"), ("%FF", "\u{FFFD}"), ("100%", "100%")];
+        for (input, expected) in cases {
+            assert_eq!(percent_decode(input), expected, "{}", input);
+        }
+    }
+    
+    #[test]
+    fn parse_location_recognizes_remote_kinds() {
+        let cases = [
+            ("file:///Users/me/app", WorkspaceLocation::Local),
+            ("vscode-remote://ssh-remote%2Bbuild-box/home/me/app", WorkspaceLocation::Ssh { host: "build-box".into() }),
+            (
+                // {"hostName":"gpu"}
+                "vscode-remote://ssh-remote+7b22686f73744e616d65223a22677075227d/home/me",
+                WorkspaceLocation::Ssh { host: "gpu".into() },
+            ),
+            ("vscode-remote://wsl%2BUbuntu/home/me/app", WorkspaceLocation::Wsl { distro: "Ubuntu".into() }),
+            (
+                // {"hostPath":"/Users/me/svc"}
+                "vscode-remote://dev-container+7b22686f737450617468223a222f55736572732f6d652f737663227d/workspaces/svc",
+                WorkspaceLocation::DevContainer { name: "svc".into() },
+            ),
+            ("vscode-remote://codespaces+fluffy-space/workspaces/app", WorkspaceLocation::Codespaces { name: "fluffy-space".into() }),
+            ("vscode-remote://tunnel+mybox/home/me", WorkspaceLocation::Remote { authority: "tunnel+mybox".into() }),
+        ];
+        for (uri, expected) in cases {
+            assert_eq!(parse_location(uri), expected, "{}", uri);
+        }
+    }
+    
+    #[test]
+    fn same_path_ignores_trailing_separator() {
+        let cases = [
+            ("/home/me/app", "/home/me/app/", true),
+            ("/home/me/app", "/home/me/app2", false),
+            ("/home/me/App", "/home/me/app", cfg!(windows)),
+            ("C:/work/app", "c:\\work\\app", cfg!(windows)),
+        ];
+        for (a, b, expected) in cases {
+            assert_eq!(same_path(a, b), expected, "{} vs {}", a, b);
+        }
+    }
+    
+    #[test]
+    fn same_project_list_matches_whole_list() {
+        let projects = vec!["/home/me/api".to_string(), "/home/me/web".to_string()];
+        let cases = [
+            ("/home/me/api + /home/me/web", true),
+            ("/home/me/api + /home/me/web/", true),
+            ("/home/me/web + /home/me/api", false),
+            ("/home/me/api", false),
+            ("/home/me/api + /home/me/web + /home/me/docs", false),
+            ("/home/me/other + /home/me/web", false),
+        ];
+        for (joined, expected) in cases {
+            assert_eq!(same_project_list(joined, &projects), expected, "{}", joined);
+        }
+    }
+    
+    #[test]
+    fn path_name_takes_last_segment() {
+        let cases = [("/home/me/app", "app"), ("/home/me/app/", "app"), ("app", "app"), ("/", "/")];
+        for (path, expected) in cases {
+            assert_eq!(path_name(path), expected, "{}", path);
+        }
+    }
+}
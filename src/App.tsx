@@ -35,6 +35,7 @@ export default function App() {
     deleting,
     deleteProgress,
     error,
+    watcherError,
     activeTab,
     appVersion,
     trashItems,
@@ -43,6 +44,7 @@ export default function App() {
     setSelectedChat,
     loadData,
     loadTrash,
+    subscribeLiveUpdates,
   } = useAppStore()
 
   // 初始化加载数据
@@ -50,6 +52,14 @@ export default function App() {
     loadData()
   }, [loadData])
 
+  // 后台文件变化时增量更新
+  useEffect(() => {
+    const unlisten = subscribeLiveUpdates()
+    return () => {
+      unlisten.then(fn => fn())
+    }
+  }, [subscribeLiveUpdates])
+
   // 处理会话点击
   const handleChatClick = (chat: ChatSession, projectName: string) => {
     setSelectedChat({ chat, projectName })
@@ -137,6 +147,11 @@ export default function App() {
             <RefreshCw size={16} /> 刷新
           </Button>
         </div>
+        {watcherError && (
+          <p className="text-sm text-destructive mt-2">
+            自动更新已暂停: {watcherError}
+          </p>
+        )}
       </header>
 
       {/* Main Content */}
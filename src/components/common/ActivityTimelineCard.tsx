@@ -0,0 +1,109 @@
+/**
+ * 活动趋势卡片：按天、周、月显示会话数量和代码行数
+ */
+import { useEffect, useState } from 'react'
+import { invoke } from '@tauri-apps/api/core'
+import {
+  ComposedChart,
+  Bar,
+  Line,
+  XAxis,
+  YAxis,
+  CartesianGrid,
+  Tooltip,
+  ResponsiveContainer,
+  Legend,
+} from 'recharts'
+import { Card, CardContent, CardHeader, CardTitle } from '@/components/ui/card'
+import { formatError, formatNumber } from '@/lib/format'
+import type { ActivityTimeline, Granularity } from '@/types'
+
+// 各粒度默认显示的时间段数量
+const RANGE_OPTIONS: Record<Granularity, { label: string; days: number }> = {
+  day: { label: '最近 30 天', days: 30 },
+  week: { label: '最近 12 周', days: 12 * 7 },
+  month: { label: '最近 12 个月', days: 365 },
+}
+
+// 按本地日期计算，和后端默认的系统时区一致
+function daysAgo(days: number): string {
+  const date = new Date()
+  date.setDate(date.getDate() - (days - 1))
+  const pad = (n: number) => String(n).padStart(2, '0')
+  return `${date.getFullYear()}-${pad(date.getMonth() + 1)}-${pad(date.getDate())}`
+}
+
+interface ActivityTimelineCardProps {
+  // 数据变化时重新加载
+  refreshKey?: unknown
+  project?: string
+}
+
+export function ActivityTimelineCard({ refreshKey, project }: ActivityTimelineCardProps) {
+  const [granularity, setGranularity] = useState<Granularity>('week')
+  const [timeline, setTimeline] = useState<ActivityTimeline | null>(null)
+  const [error, setError] = useState<string | null>(null)
+
+  useEffect(() => {
+    invoke<ActivityTimeline>('get_activity_timeline', {
+      granularity,
+      range: { start: daysAgo(RANGE_OPTIONS[granularity].days), end: null },
+      project: project ?? null,
+    })
+      .then((result) => {
+        setTimeline(result)
+        setError(null)
+      })
+      .catch((e) => setError(formatError(e)))
+  }, [granularity, project, refreshKey])
+
+  return (
+    <Card className="bg-card border-border">
+      <CardHeader className="flex flex-row items-center justify-between">
+        <CardTitle className="text-foreground">活动趋势</CardTitle>
+        <select
+          value={granularity}
+          onChange={(e) => setGranularity(e.target.value as Granularity)}
+          className="h-9 px-3 rounded-md bg-secondary border border-border text-sm text-foreground"
+        >
+          {(Object.keys(RANGE_OPTIONS) as Granularity[]).map((g) => (
+            <option key={g} value={g}>
+              {RANGE_OPTIONS[g].label}
+            </option>
+          ))}
+        </select>
+      </CardHeader>
+      <CardContent>
+        {error && <p className="text-sm text-destructive">{error}</p>}
+        {timeline && (
+          <ResponsiveContainer width="100%" height={300}>
+            <ComposedChart data={timeline.buckets} margin={{ left: 20, right: 20 }}>
+              <CartesianGrid strokeDasharray="3 3" stroke="#30363d" />
+              <XAxis dataKey="label" tick={{ fontSize: 12, fill: '#8b949e' }} stroke="#8b949e" />
+              <YAxis yAxisId="lines" tickFormatter={(v) => formatNumber(v)} stroke="#8b949e" />
+              <YAxis yAxisId="chats" orientation="right" allowDecimals={false} stroke="#8b949e" />
+              <Tooltip
+                formatter={(v) => formatNumber(Number(v))}
+                contentStyle={{
+                  backgroundColor: '#161b22',
+                  border: '1px solid #30363d',
+                  borderRadius: '8px',
+                }}
+              />
+              <Legend />
+              <Bar yAxisId="lines" dataKey="lines_added" fill="#3fb950" name="添加行数" />
+              <Bar yAxisId="lines" dataKey="lines_removed" fill="#f85149" name="删除行数" />
+              <Line yAxisId="chats" dataKey="agent_count" stroke="#a371f7" name="Agent 会话" dot={false} />
+              <Line yAxisId="chats" dataKey="chat_mode_count" stroke="#58a6ff" name="Chat 会话" dot={false} />
+            </ComposedChart>
+          </ResponsiveContainer>
+        )}
+        {timeline && timeline.undated_count > 0 && (
+          <p className="text-xs text-muted-foreground mt-2">
+            {timeline.undated_count} 个会话没有时间信息，未计入
+          </p>
+        )}
+      </CardContent>
+    </Card>
+  )
+}
@@ -1,3 +1,4 @@
 export { StatCard } from './StatCard'
 export { Pagination } from './Pagination'
 export { ConfirmDialog } from './ConfirmDialog'
+export { ActivityTimelineCard } from './ActivityTimelineCard'
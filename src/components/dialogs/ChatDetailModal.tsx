@@ -1,6 +1,8 @@
 /**
  * 会话详情弹窗组件
  */
+import { useEffect, useState } from 'react'
+import { invoke } from '@tauri-apps/api/core'
 import { 
   Bot, 
   MessageCircle, 
@@ -11,7 +13,9 @@ import {
   Trash2, 
   FileCode, 
   FolderOpen, 
-  Archive 
+  Archive,
+  User,
+  Wrench
 } from 'lucide-react'
 import {
   Dialog,
@@ -23,8 +27,8 @@ import { Badge } from '@/components/ui/badge'
 import { Button } from '@/components/ui/button'
 import { Separator } from '@/components/ui/separator'
 import { cn } from '@/lib/utils'
-import { formatNumber } from '@/lib/format'
-import type { ChatSession } from '@/types'
+import { formatError, formatNumber } from '@/lib/format'
+import type { ChatSession, ChatTranscript } from '@/types'
 
 interface ChatDetailModalProps {
   chat: ChatSession | null
@@ -41,6 +45,23 @@ export function ChatDetailModal({
   onClose,
   onDelete,
 }: ChatDetailModalProps) {
+  const [transcript, setTranscript] = useState<ChatTranscript | null>(null)
+  const [transcriptError, setTranscriptError] = useState<string | null>(null)
+
+  // 打开弹窗时加载会话全文
+  useEffect(() => {
+    if (!open || !chat) return
+    let cancelled = false
+    setTranscript(null)
+    setTranscriptError(null)
+    invoke<ChatTranscript>('get_chat_transcript', { chatId: chat.id })
+      .then((t) => !cancelled && setTranscript(t))
+      .catch((e) => !cancelled && setTranscriptError(formatError(e)))
+    return () => {
+      cancelled = true
+    }
+  }, [open, chat])
+
   if (!chat) return null
 
   const isAgent = chat.mode === 'agent'
@@ -167,6 +188,61 @@ export function ChatDetailModal({
             </>
           )}
 
+          {/* 会话内容 */}
+          <Separator className="bg-border" />
+          <div>
+            <h4 className="text-sm font-medium text-foreground mb-2">会话内容</h4>
+            {transcriptError && (
+              <p className="text-sm text-destructive">{transcriptError}</p>
+            )}
+            {!transcript && !transcriptError && (
+              <p className="text-sm text-muted-foreground">加载中...</p>
+            )}
+            {transcript && transcript.messages.length === 0 && (
+              <p className="text-sm text-muted-foreground">没有找到消息记录</p>
+            )}
+            {transcript && transcript.messages.length > 0 && (
+              <div className="space-y-3">
+                {transcript.messages.map((m) => (
+                  <div
+                    key={m.bubble_id}
+                    className={cn(
+                      'p-3 rounded-lg text-sm',
+                      m.role === 'user' ? 'bg-primary/10' : 'bg-secondary/50'
+                    )}
+                  >
+                    <div className="flex items-center gap-2 text-xs text-muted-foreground mb-1">
+                      {m.role === 'user' ? <User size={12} /> : <Bot size={12} />}
+                      <span>{m.role === 'user' ? '用户' : 'AI'}</span>
+                      {m.created_at && <span>{m.created_at}</span>}
+                    </div>
+                    {m.text && (
+                      <p className="text-foreground whitespace-pre-wrap break-words">{m.text}</p>
+                    )}
+                    {m.code_blocks.map((b, i) => (
+                      <pre
+                        key={i}
+                        className="mt-2 p-2 bg-secondary rounded text-xs font-mono overflow-x-auto"
+                      >
+                        {b.file_path && (
+                          <div className="text-muted-foreground mb-1">{b.file_path}</div>
+                        )}
+                        {b.content}
+                      </pre>
+                    ))}
+                    {m.tool_calls.map((t, i) => (
+                      <div key={i} className="mt-2 flex items-center gap-2 text-xs text-muted-foreground">
+                        <Wrench size={12} />
+                        <span className="font-mono">{t.name}</span>
+                        {t.status && <span>({t.status})</span>}
+                      </div>
+                    ))}
+                  </div>
+                ))}
+              </div>
+            )}
+          </div>
+
           {/* ID */}
           <div>
             <p className="text-xs text-muted-foreground mb-1">会话 ID</p>
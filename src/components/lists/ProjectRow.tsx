@@ -8,7 +8,7 @@ import { Badge } from '@/components/ui/badge'
 import { Button } from '@/components/ui/button'
 import { Checkbox } from '@/components/ui/checkbox'
 import { cn } from '@/lib/utils'
-import { formatNumber } from '@/lib/format'
+import { formatLocation, formatNumber } from '@/lib/format'
 import { ConfirmDialog } from '@/components/common/ConfirmDialog'
 import { UnifiedChatList } from './UnifiedChatList'
 import type { ProjectStats, ChatSession } from '@/types'
@@ -75,7 +75,14 @@ export function ProjectRow({
 
         {/* 项目信息 */}
         <div className="flex-1 min-w-0">
-          <p className="font-medium text-foreground truncate">{project.name}</p>
+          <div className="flex items-center gap-2 min-w-0">
+            <p className="font-medium text-foreground truncate">{project.name}</p>
+            {project.location.kind !== 'local' && (
+              <Badge variant="outline" className="flex-shrink-0 text-xs">
+                {formatLocation(project.location)}
+              </Badge>
+            )}
+          </div>
           <p className="text-xs text-muted-foreground truncate">
             {project.path.replace('/Users/cong/', '~/')}
           </p>
@@ -21,7 +21,8 @@ import { Badge } from '@/components/ui/badge'
 import { Checkbox } from '@/components/ui/checkbox'
 import { Card } from '@/components/ui/card'
 import { cn } from '@/lib/utils'
-import { formatNumber } from '@/lib/format'
+import { formatNumber, formatSize } from '@/lib/format'
+import { chatModeKind } from '@/lib/chatModes'
 import { Pagination } from '@/components/common/Pagination'
 import { ConfirmDialog } from '@/components/common/ConfirmDialog'
 import type { ChatSession, SortField, SortOrder } from '@/types'
@@ -73,7 +74,7 @@ export function UnifiedChatList({
         const matchSubtitle = chat.subtitle.toLowerCase().includes(search)
         if (!matchName && !matchSubtitle) return false
       }
-      if (modeFilter !== 'all' && chat.mode !== modeFilter) return false
+      if (modeFilter !== 'all' && chatModeKind(chat.mode) !== modeFilter) return false
       if (archiveFilter === 'archived' && !chat.is_archived) return false
       if (archiveFilter === 'active' && chat.is_archived) return false
       return true
@@ -95,10 +96,10 @@ export function UnifiedChatList({
           cmp = (a.lines_added - a.lines_removed) - (b.lines_added - b.lines_removed)
           break
         case 'updated_at':
-          cmp = (a.updated_at || '').localeCompare(b.updated_at || '')
+          cmp = (a.updated_at_ms ?? 0) - (b.updated_at_ms ?? 0)
           break
         case 'created_at':
-          cmp = (a.created_at || '').localeCompare(b.created_at || '')
+          cmp = (a.created_at_ms ?? 0) - (b.created_at_ms ?? 0)
           break
         case 'name':
           cmp = a.name.localeCompare(b.name)
@@ -106,6 +107,9 @@ export function UnifiedChatList({
         case 'context_usage':
           cmp = a.context_usage - b.context_usage
           break
+        case 'storage_size':
+          cmp = a.storage_size - b.storage_size
+          break
         default:
           cmp = 0
       }
@@ -199,7 +203,7 @@ export function UnifiedChatList({
           >
             <option value="all">全部模式</option>
             <option value="agent">Agent</option>
-            <option value="chat">Chat</option>
+            <option value="chat">Ask</option>
           </select>
           <select
             value={archiveFilter}
@@ -233,6 +237,7 @@ export function UnifiedChatList({
             <option value="updated_at">更新时间</option>
             <option value="created_at">创建时间</option>
             <option value="context_usage">上下文使用</option>
+            <option value="storage_size">占用空间</option>
             <option value="name">名称</option>
           </select>
           <Button
@@ -349,6 +354,11 @@ export function UnifiedChatList({
                     <Badge variant="secondary" className="bg-info/20 text-info border-0">
                       {chat.files_changed} 文件
                     </Badge>
+                    {chat.storage_size > 0 && (
+                      <Badge variant="secondary" className="bg-muted text-muted-foreground border-0">
+                        {formatSize(chat.storage_size)}
+                      </Badge>
+                    )}
                   </div>
                   {chat.subtitle && (
                     <p className="text-xs text-muted-foreground mt-2 truncate">
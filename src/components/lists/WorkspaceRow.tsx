@@ -8,7 +8,7 @@ import { Badge } from '@/components/ui/badge'
 import { Button } from '@/components/ui/button'
 import { Checkbox } from '@/components/ui/checkbox'
 import { cn } from '@/lib/utils'
-import { formatNumber } from '@/lib/format'
+import { basename, formatNumber } from '@/lib/format'
 import { ConfirmDialog } from '@/components/common/ConfirmDialog'
 import { UnifiedChatList } from './UnifiedChatList'
 import type { WorkspaceInfo, ChatSession } from '@/types'
@@ -36,7 +36,7 @@ export function WorkspaceRow({
   const [deleteConfirm, setDeleteConfirm] = useState(false)
 
   const projectPath = workspace.projects[0] || workspace.id
-  const projectName = workspace.projects[0]?.split('/').pop() || 'Unknown'
+  const projectName = (workspace.projects[0] && basename(workspace.projects[0])) || 'Unknown'
 
   const handleDeleteClick = (e: React.MouseEvent) => {
     e.stopPropagation()
@@ -92,7 +92,7 @@ export function WorkspaceRow({
         <div className="flex items-center gap-1 flex-shrink-0">
           {workspace.projects.slice(0, 2).map((p, i) => (
             <Badge key={i} variant="outline" className="text-xs">
-              {p.split('/').pop()}
+              {basename(p)}
             </Badge>
           ))}
           {workspace.projects.length > 2 && (
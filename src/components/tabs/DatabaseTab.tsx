@@ -28,13 +28,32 @@ interface DatabaseTabProps {
   data: AnalysisResult
 }
 
+/** 已知前缀的说明，其他前缀只显示名称 */
+const prefixDescriptions: Record<string, string> = {
+  bubbleId: '每条对话消息',
+  checkpointId: 'Agent 模式检查点',
+  agentKv: 'Agent 运行时数据',
+  composerData: 'Composer 会话',
+}
+
+/** 图表只显示最大的几个前缀，其余合并为“其他” */
+const CHART_PREFIX_LIMIT = 6
+
 export function DatabaseTab({ data }: DatabaseTabProps) {
-  const databaseChartData = [
-    { name: '对话气泡', value: data.database.bubble_size, count: data.database.bubble_count },
-    { name: '检查点', value: data.database.checkpoint_size, count: data.database.checkpoint_count },
-    { name: 'Agent数据', value: data.database.agent_kv_size, count: data.database.agent_kv_count },
-    { name: 'Composer', value: data.database.composer_size, count: data.database.composer_count },
-  ]
+  const prefixes = data.database.cursor_disk_kv_prefixes
+  const databaseChartData = prefixes.slice(0, CHART_PREFIX_LIMIT).map((p) => ({
+    name: p.prefix,
+    value: p.total_size,
+    count: p.count,
+  }))
+  const rest = prefixes.slice(CHART_PREFIX_LIMIT)
+  if (rest.length > 0) {
+    databaseChartData.push({
+      name: '其他',
+      value: rest.reduce((sum, p) => sum + p.total_size, 0),
+      count: rest.reduce((sum, p) => sum + p.count, 0),
+    })
+  }
 
   return (
     <div className="space-y-6">
@@ -120,34 +139,24 @@ export function DatabaseTab({ data }: DatabaseTabProps) {
                 <TableHead className="text-muted-foreground">类别</TableHead>
                 <TableHead className="text-muted-foreground">记录数</TableHead>
                 <TableHead className="text-muted-foreground">大小</TableHead>
+                <TableHead className="text-muted-foreground">平均</TableHead>
+                <TableHead className="text-muted-foreground">最大</TableHead>
                 <TableHead className="text-muted-foreground">说明</TableHead>
               </TableRow>
             </TableHeader>
             <TableBody>
-              <TableRow className="border-border">
-                <TableCell className="font-medium text-foreground">bubbleId</TableCell>
-                <TableCell className="text-foreground">{formatNumber(data.database.bubble_count)}</TableCell>
-                <TableCell className="text-foreground">{formatSize(data.database.bubble_size)}</TableCell>
-                <TableCell className="text-muted-foreground">每条对话消息</TableCell>
-              </TableRow>
-              <TableRow className="border-border">
-                <TableCell className="font-medium text-foreground">checkpointId</TableCell>
-                <TableCell className="text-foreground">{formatNumber(data.database.checkpoint_count)}</TableCell>
-                <TableCell className="text-foreground">{formatSize(data.database.checkpoint_size)}</TableCell>
-                <TableCell className="text-muted-foreground">Agent 模式检查点</TableCell>
-              </TableRow>
-              <TableRow className="border-border">
-                <TableCell className="font-medium text-foreground">agentKv</TableCell>
-                <TableCell className="text-foreground">{formatNumber(data.database.agent_kv_count)}</TableCell>
-                <TableCell className="text-foreground">{formatSize(data.database.agent_kv_size)}</TableCell>
-                <TableCell className="text-muted-foreground">Agent 运行时数据</TableCell>
-              </TableRow>
-              <TableRow className="border-border">
-                <TableCell className="font-medium text-foreground">composerData</TableCell>
-                <TableCell className="text-foreground">{formatNumber(data.database.composer_count)}</TableCell>
-                <TableCell className="text-foreground">{formatSize(data.database.composer_size)}</TableCell>
-                <TableCell className="text-muted-foreground">Composer 会话</TableCell>
-              </TableRow>
+              {prefixes.map((p) => (
+                <TableRow key={p.prefix} className="border-border">
+                  <TableCell className="font-medium text-foreground">{p.prefix}</TableCell>
+                  <TableCell className="text-foreground">{formatNumber(p.count)}</TableCell>
+                  <TableCell className="text-foreground">{formatSize(p.total_size)}</TableCell>
+                  <TableCell className="text-foreground">{formatSize(p.avg_size)}</TableCell>
+                  <TableCell className="text-foreground">{formatSize(p.max_size)}</TableCell>
+                  <TableCell className="text-muted-foreground">
+                    {prefixDescriptions[p.prefix] ?? ''}
+                  </TableCell>
+                </TableRow>
+              ))}
             </TableBody>
           </Table>
         </CardContent>
@@ -1,6 +1,8 @@
 /**
  * 概览 Tab 组件
  */
+import { useEffect, useState } from 'react'
+import { invoke } from '@tauri-apps/api/core'
 import {
   FolderOpen,
   MessageSquare,
@@ -24,19 +26,29 @@ import {
 } from 'recharts'
 import { Card, CardContent, CardHeader, CardTitle } from '@/components/ui/card'
 import { StatCard } from '@/components/common/StatCard'
+import { ActivityTimelineCard } from '@/components/common/ActivityTimelineCard'
 import { formatNumber } from '@/lib/format'
 import { chartColors } from '@/design-system/tokens'
-import type { AnalysisResult } from '@/types'
+import type { AnalysisResult, UsageTotals } from '@/types'
 
 interface OverviewTabProps {
   data: AnalysisResult
 }
 
 export function OverviewTab({ data }: OverviewTabProps) {
-  const modeChartData = [
-    { name: 'Agent 模式', value: data.overview.agent_mode_count },
-    { name: 'Chat 模式', value: data.overview.chat_mode_count },
-  ]
+  const [usage, setUsage] = useState<UsageTotals | null>(null)
+
+  // 每次加载数据时后端已记录一次，这里读取累计结果
+  useEffect(() => {
+    invoke<UsageTotals>('get_usage_totals')
+      .then(setUsage)
+      .catch((e) => console.error('加载长期统计失败:', e))
+  }, [data])
+
+  // 每种模式一块，按会话数量降序
+  const modeChartData = Object.values(data.overview.modes)
+    .sort((a, b) => b.chat_count - a.chat_count)
+    .map((m) => ({ name: m.label, value: m.chat_count }))
 
   const topProjects = data.projects.slice(0, 10).map((p) => ({
     name: p.name,
@@ -113,7 +125,7 @@ export function OverviewTab({ data }: OverviewTabProps) {
                   {modeChartData.map((_, index) => (
                     <Cell
                       key={`cell-${index}`}
-                      fill={index === 0 ? '#a371f7' : '#58a6ff'}
+                      fill={chartColors[index % chartColors.length]}
                     />
                   ))}
                 </Pie>
@@ -171,6 +183,75 @@ export function OverviewTab({ data }: OverviewTabProps) {
           </CardContent>
         </Card>
       </div>
+
+      {/* 活动趋势 */}
+      <ActivityTimelineCard refreshKey={data} />
+
+      {/* 远程工作区分布 */}
+      {data.overview.locations.length > 1 && (
+        <Card className="bg-card border-border">
+          <CardHeader>
+            <CardTitle className="text-foreground">工作区位置分布</CardTitle>
+          </CardHeader>
+          <CardContent>
+            <div className="grid grid-cols-1 md:grid-cols-2 lg:grid-cols-4 gap-3">
+              {data.overview.locations.map((l) => (
+                <div key={l.label} className="p-3 bg-secondary/50 rounded-lg">
+                  <p className="text-sm font-medium text-foreground truncate">{l.label}</p>
+                  <p className="text-xs text-muted-foreground mt-1">
+                    {l.project_count} 项目 · {l.chat_count} 会话 · +{formatNumber(l.lines_added)} / -
+                    {formatNumber(l.lines_removed)}
+                  </p>
+                </div>
+              ))}
+            </div>
+          </CardContent>
+        </Card>
+      )}
+
+      {/* 长期统计：包括已在 Cursor 中删除的会话 */}
+      {usage && usage.chat_count > 0 && (
+        <Card className="bg-card border-border">
+          <CardHeader>
+            <CardTitle className="text-foreground">累计统计</CardTitle>
+            {data.usage_error && (
+              <p className="text-xs text-destructive">本次记录失败: {data.usage_error}</p>
+            )}
+          </CardHeader>
+          <CardContent>
+            <div className="grid grid-cols-2 md:grid-cols-4 gap-3">
+              <div className="p-3 bg-secondary/50 rounded-lg">
+                <p className="text-xs text-muted-foreground">记录过的会话</p>
+                <p className="text-lg font-semibold text-foreground">{formatNumber(usage.chat_count)}</p>
+                <p className="text-xs text-muted-foreground mt-1">已删除 {formatNumber(usage.deleted_chat_count)}</p>
+              </div>
+              <div className="p-3 bg-secondary/50 rounded-lg">
+                <p className="text-xs text-muted-foreground">累计新增代码</p>
+                <p className="text-lg font-semibold text-foreground">+{formatNumber(usage.lines_added)}</p>
+                <p className="text-xs text-muted-foreground mt-1">
+                  来自已删除会话 +{formatNumber(usage.deleted_lines_added)}
+                </p>
+              </div>
+              <div className="p-3 bg-secondary/50 rounded-lg">
+                <p className="text-xs text-muted-foreground">累计删除代码</p>
+                <p className="text-lg font-semibold text-foreground">-{formatNumber(usage.lines_removed)}</p>
+                <p className="text-xs text-muted-foreground mt-1">
+                  来自已删除会话 -{formatNumber(usage.deleted_lines_removed)}
+                </p>
+              </div>
+              <div className="p-3 bg-secondary/50 rounded-lg">
+                <p className="text-xs text-muted-foreground">记录开始于</p>
+                <p className="text-lg font-semibold text-foreground">
+                  {usage.first_recorded_at?.slice(0, 10) ?? '-'}
+                </p>
+                <p className="text-xs text-muted-foreground mt-1">
+                  修改文件 {formatNumber(usage.files_changed)}
+                </p>
+              </div>
+            </div>
+          </CardContent>
+        </Card>
+      )}
     </div>
   )
 }
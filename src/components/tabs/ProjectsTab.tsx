@@ -48,6 +48,8 @@ export function ProjectsTab({ onChatClick }: ProjectsTabProps) {
         return projects.sort((a, b) => b.chat_count - a.chat_count)
       case 'files_changed':
         return projects.sort((a, b) => b.files_changed - a.files_changed)
+      case 'storage_size':
+        return projects.sort((a, b) => b.storage_size - a.storage_size)
       case 'name':
         return projects.sort((a, b) => a.name.localeCompare(b.name))
       default:
@@ -126,6 +128,7 @@ export function ProjectsTab({ onChatClick }: ProjectsTabProps) {
             <option value="lines_removed">删除代码量</option>
             <option value="chat_count">会话数量</option>
             <option value="files_changed">变更文件数</option>
+            <option value="storage_size">占用空间</option>
             <option value="name">项目名称</option>
           </select>
         </div>
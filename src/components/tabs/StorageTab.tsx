@@ -1,7 +1,9 @@
 /**
  * 存储 Tab 组件
  */
-import { HardDrive, Database, FolderOpen, Lightbulb } from 'lucide-react'
+import { useEffect, useState } from 'react'
+import { invoke } from '@tauri-apps/api/core'
+import { HardDrive, Database, FolderOpen, Lightbulb, History } from 'lucide-react'
 import {
   BarChart,
   Bar,
@@ -14,15 +16,24 @@ import {
 } from 'recharts'
 import { Card, CardContent, CardHeader, CardTitle } from '@/components/ui/card'
 import { StatCard } from '@/components/common/StatCard'
-import { formatSize } from '@/lib/format'
+import { formatError, formatSize } from '@/lib/format'
 import { chartColors } from '@/design-system/tokens'
-import type { AnalysisResult } from '@/types'
+import type { AnalysisResult, HistoryReport } from '@/types'
 
 interface StorageTabProps {
   data: AnalysisResult
 }
 
 export function StorageTab({ data }: StorageTabProps) {
+  const [history, setHistory] = useState<HistoryReport | null>(null)
+  const [historyError, setHistoryError] = useState<string | null>(null)
+
+  useEffect(() => {
+    invoke<HistoryReport>('get_history_report')
+      .then(setHistory)
+      .catch((e) => setHistoryError(formatError(e)))
+  }, [data])
+
   const storageChartData = [
     {
       name: 'globalStorage',
@@ -103,6 +114,54 @@ export function StorageTab({ data }: StorageTabProps) {
         </CardContent>
       </Card>
 
+      {/* History 分析 */}
+      <Card className="bg-card border-border">
+        <CardHeader>
+          <CardTitle className="text-foreground flex items-center gap-2">
+            <History size={18} /> 文件历史（History）
+          </CardTitle>
+        </CardHeader>
+        <CardContent>
+          {historyError && <p className="text-sm text-destructive">{historyError}</p>}
+          {!history && !historyError && <p className="text-sm text-muted-foreground">分析中...</p>}
+          {history && (
+            <div className="space-y-3">
+              <p className="text-sm text-muted-foreground">
+                共 {history.file_count} 个文件、{history.entry_count} 个版本，占用 {history.total_size_human}
+                ；源文件已删除的历史 {history.missing_file_count} 个，占用 {formatSize(history.missing_size)}
+              </p>
+              <table className="w-full text-sm">
+                <thead>
+                  <tr className="text-left text-muted-foreground">
+                    <th className="py-1 font-normal">项目</th>
+                    <th className="py-1 font-normal">大小</th>
+                    <th className="py-1 font-normal">文件</th>
+                    <th className="py-1 font-normal">已删除文件</th>
+                    <th className="py-1 font-normal">最早版本</th>
+                  </tr>
+                </thead>
+                <tbody>
+                  {history.projects.slice(0, 10).map((p) => (
+                    <tr key={p.project_path} className="border-t border-border">
+                      <td className="py-1 text-foreground" title={p.project_path}>
+                        {p.name}
+                        {p.exists === false && (
+                          <span className="ml-2 text-xs text-destructive">项目已删除</span>
+                        )}
+                      </td>
+                      <td className="py-1 text-muted-foreground">{p.total_size_human}</td>
+                      <td className="py-1 text-muted-foreground">{p.file_count}</td>
+                      <td className="py-1 text-muted-foreground">{p.missing_file_count}</td>
+                      <td className="py-1 text-muted-foreground">{p.oldest || '-'}</td>
+                    </tr>
+                  ))}
+                </tbody>
+              </table>
+            </div>
+          )}
+        </CardContent>
+      </Card>
+
       {/* 清理建议 */}
       <Card className="bg-card border-border">
         <CardHeader>
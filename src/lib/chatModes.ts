@@ -0,0 +1,63 @@
+/**
+ * 会话模式的归一化和汇总，与后端 chat_mode 模块保持一致，供实时更新时重新计算
+ */
+import type { ChatMode, ChatSession, ModeStats } from '@/types'
+
+export function chatModeKind(mode: string): ChatMode {
+  switch (mode.trim().toLowerCase()) {
+    case 'agent':
+      return 'agent'
+    case 'chat':
+    case 'ask':
+      return 'chat'
+    case 'edit':
+      return 'edit'
+    case 'plan':
+      return 'plan'
+    case 'background':
+      return 'background'
+    case '':
+    case 'unknown':
+      return 'unknown'
+    default:
+      return 'other'
+  }
+}
+
+const MODE_LABELS: Record<ChatMode, string> = {
+  agent: 'Agent',
+  chat: 'Ask',
+  edit: 'Edit',
+  plan: 'Plan',
+  background: 'Background',
+  unknown: '未知',
+  other: '其他',
+}
+
+/**
+ * 按归一化后的模式汇总，原始值及其会话数记在 raw_modes 中
+ */
+export function modeBreakdown(chats: ChatSession[]): Record<string, ModeStats> {
+  const result: Record<string, ModeStats> = {}
+  for (const chat of chats) {
+    const kind = chatModeKind(chat.mode)
+    if (!result[kind]) {
+      result[kind] = {
+        kind,
+        label: MODE_LABELS[kind],
+        chat_count: 0,
+        lines_added: 0,
+        lines_removed: 0,
+        files_changed: 0,
+        raw_modes: {},
+      }
+    }
+    const stats = result[kind]
+    stats.raw_modes[chat.mode] = (stats.raw_modes[chat.mode] ?? 0) + 1
+    stats.chat_count += 1
+    stats.lines_added += chat.lines_added
+    stats.lines_removed += chat.lines_removed
+    stats.files_changed += chat.files_changed
+  }
+  return result
+}
@@ -1,6 +1,7 @@
 /**
  * 格式化工具函数
  */
+import type { WorkspaceLocation } from '@/types'
 
 /**
  * 格式化数字，添加千位分隔符
@@ -28,13 +29,20 @@ export function truncate(str: string, maxLength: number): string {
   return str.slice(0, maxLength - 3) + '...'
 }
 
+/**
+ * 路径最后一段（兼容 Windows 分隔符）
+ */
+export function basename(path: string): string {
+  return path.split(/[\\/]/).filter(Boolean).pop() || ''
+}
+
 /**
  * 缩短文件路径
  */
 export function shortenPath(path: string, maxLength: number = 40): string {
   if (path.length <= maxLength) return path
   
-  const parts = path.split('/')
+  const parts = path.split(/[\\/]/)
   if (parts.length <= 2) return truncate(path, maxLength)
   
   const first = parts[0]
@@ -67,3 +75,33 @@ export function formatDate(timestamp: number): string {
     minute: '2-digit',
   })
 }
+
+/**
+ * 格式化后端返回的错误（结构化错误或字符串）
+ */
+export function formatError(e: unknown): string {
+  if (e && typeof e === 'object' && 'message' in e) {
+    return String((e as { message: unknown }).message)
+  }
+  return String(e)
+}
+
+/**
+ * 工作区位置的显示名称
+ */
+export function formatLocation(location: WorkspaceLocation): string {
+  switch (location.kind) {
+    case 'local':
+      return '本地'
+    case 'ssh':
+      return `SSH: ${location.host}`
+    case 'wsl':
+      return `WSL: ${location.distro}`
+    case 'dev_container':
+      return `Dev Container: ${location.name}`
+    case 'codespaces':
+      return `Codespaces: ${location.name}`
+    case 'remote':
+      return `远程: ${location.authority}`
+  }
+}
@@ -0,0 +1,156 @@
+/**
+ * 把文件监听事件合并到已加载的分析结果中，避免每次变化都重新加载全部数据
+ */
+import type {
+  AnalysisResult,
+  ChatEvent,
+  ChatSession,
+  OverviewStats,
+  ProjectStats,
+  StorageChange,
+  WorkspaceInfo,
+} from '@/types'
+import { modeBreakdown } from '@/lib/chatModes'
+
+/**
+ * 按会话列表重新计算汇总字段
+ */
+function withTotals<T extends ProjectStats | WorkspaceInfo>(item: T, chats: ChatSession[]): T {
+  const sum = (pick: (c: ChatSession) => number) => chats.reduce((acc, c) => acc + pick(c), 0)
+  return {
+    ...item,
+    chat_count: chats.length,
+    lines_added: sum(c => c.lines_added),
+    lines_removed: sum(c => c.lines_removed),
+    files_changed: sum(c => c.files_changed),
+    storage_size: sum(c => c.storage_size),
+  }
+}
+
+function upsertChat(chats: ChatSession[], chat: ChatSession): ChatSession[] {
+  const index = chats.findIndex(c => c.id === chat.id)
+  if (index === -1) return [chat, ...chats]
+  const next = [...chats]
+  next[index] = chat
+  return next
+}
+
+/**
+ * 概览只统计项目，按项目列表重新汇总；位置分布沿用已有的标签
+ */
+function recomputeOverview(overview: OverviewStats, projects: ProjectStats[]): OverviewStats {
+  const chats = projects.flatMap(p => p.chats)
+  const totalLinesAdded = projects.reduce((acc, p) => acc + p.lines_added, 0)
+  const totalLinesRemoved = projects.reduce((acc, p) => acc + p.lines_removed, 0)
+  const modes = modeBreakdown(chats)
+  const agentCount = Object.values(modes)
+    .filter(m => m.kind === 'agent')
+    .reduce((acc, m) => acc + m.chat_count, 0)
+  
+  const locations = overview.locations.map(usage => {
+    const matched = projects.filter(p => JSON.stringify(p.location) === JSON.stringify(usage.location))
+    return {
+      ...usage,
+      project_count: matched.length,
+      chat_count: matched.reduce((acc, p) => acc + p.chat_count, 0),
+      lines_added: matched.reduce((acc, p) => acc + p.lines_added, 0),
+      lines_removed: matched.reduce((acc, p) => acc + p.lines_removed, 0),
+    }
+  })
+  
+  return {
+    ...overview,
+    total_projects: projects.length,
+    total_chats: chats.length,
+    total_lines_added: totalLinesAdded,
+    total_lines_removed: totalLinesRemoved,
+    net_lines: totalLinesAdded - totalLinesRemoved,
+    total_files_changed: projects.reduce((acc, p) => acc + p.files_changed, 0),
+    agent_mode_count: agentCount,
+    chat_mode_count: chats.length - agentCount,
+    modes,
+    locations,
+  }
+}
+
+/**
+ * 合并新增或更新的会话；返回 null 表示涉及尚未加载的工作区或项目，需要重新加载
+ */
+export function applyChatEvent(data: AnalysisResult, event: ChatEvent): AnalysisResult | null {
+  const workspace = data.workspaces.find(w => w.id === event.workspace_id)
+  if (!workspace) return null
+  
+  let projects = data.projects
+  if (event.project_path) {
+    const project = projects.find(p => p.path === event.project_path)
+    if (!project) return null
+    const chats = upsertChat(project.chats, event.chat)
+    projects = projects.map(p =>
+      p === project ? { ...withTotals(p, chats), modes: modeBreakdown(chats), chats } : p
+    )
+  }
+  
+  const recentChats = upsertChat(workspace.recent_chats, event.chat)
+  const workspaces = data.workspaces.map(w =>
+    w === workspace ? { ...withTotals(w, recentChats), recent_chats: recentChats } : w
+  )
+  
+  return {
+    ...data,
+    projects,
+    workspaces,
+    overview: recomputeOverview(data.overview, projects),
+  }
+}
+
+/**
+ * 移除已删除的会话和工作区，清空后的项目和工作区不再显示
+ */
+export function applyStorageChange(data: AnalysisResult, change: StorageChange): AnalysisResult {
+  if (change.removed_chats.length === 0 && change.removed_workspaces.length === 0) {
+    return data
+  }
+  
+  const removedWorkspaces = new Set(change.removed_workspaces)
+  const removedChats = new Map<string, Set<string>>()
+  for (const { workspace_id, chat_id } of change.removed_chats) {
+    if (!removedChats.has(workspace_id)) removedChats.set(workspace_id, new Set())
+    removedChats.get(workspace_id)!.add(chat_id)
+  }
+  
+  // 单项目工作区的会话同时出现在对应项目中
+  const removedFromProjects = new Map<string, Set<string>>()
+  for (const w of data.workspaces) {
+    const removed = removedChats.get(w.id)
+    if (removed && !w.is_multi_project && w.projects[0]) {
+      const path = w.projects[0]
+      removedFromProjects.set(path, new Set([...(removedFromProjects.get(path) ?? []), ...removed]))
+    }
+  }
+  
+  const workspaces = data.workspaces
+    .filter(w => !removedWorkspaces.has(w.id))
+    .map(w => {
+      const removed = removedChats.get(w.id)
+      if (!removed) return w
+      const recentChats = w.recent_chats.filter(c => !removed.has(c.id))
+      return { ...withTotals(w, recentChats), recent_chats: recentChats }
+    })
+    .filter(w => w.chat_count > 0)
+  
+  const projects = data.projects
+    .map(p => {
+      const removed = removedFromProjects.get(p.path)
+      if (!removed) return p
+      const chats = p.chats.filter(c => !removed.has(c.id))
+      return { ...withTotals(p, chats), modes: modeBreakdown(chats), chats }
+    })
+    .filter(p => p.chat_count > 0)
+  
+  return {
+    ...data,
+    projects,
+    workspaces,
+    overview: recomputeOverview(data.overview, projects),
+  }
+}
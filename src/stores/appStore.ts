@@ -3,13 +3,19 @@
  */
 import { create } from 'zustand'
 import { invoke } from '@tauri-apps/api/core'
+import { listen, type UnlistenFn } from '@tauri-apps/api/event'
 import type { 
   AnalysisResult, 
   TrashItem, 
   ChatSession, 
+  ChatEvent, 
+  StorageChange, 
+  WatcherError,
   TabType, 
   ProjectSortField 
 } from '@/types'
+import { basename, formatError } from '@/lib/format'
+import { applyChatEvent, applyStorageChange } from '@/lib/liveUpdates'
 
 interface SelectedChat {
   chat: ChatSession
@@ -33,6 +39,8 @@ interface AppState {
   deleting: boolean
   deleteProgress: DeleteProgress | null
   error: string | null
+  // 后台文件监听的错误，恢复后清空
+  watcherError: string | null
   activeTab: TabType
   
   // 项目分页和排序
@@ -62,6 +70,8 @@ interface AppState {
   
   // 异步 Actions
   loadData: () => Promise<void>
+  refreshData: () => Promise<void>
+  subscribeLiveUpdates: () => Promise<UnlistenFn>
   loadTrash: () => Promise<void>
   deleteChat: (projectPath: string, chatId: string) => Promise<void>
   deleteChatsBatch: (projectPath: string, chatIds: string[]) => Promise<void>
@@ -82,6 +92,7 @@ export const useAppStore = create<AppState>((set, get) => ({
   deleting: false,
   deleteProgress: null,
   error: null,
+  watcherError: null,
   activeTab: 'overview',
   projectPage: 1,
   projectSort: 'lines_added',
@@ -133,6 +144,52 @@ export const useAppStore = create<AppState>((set, get) => ({
     }
   },
   
+  // 静默刷新，不显示加载状态
+  refreshData: async () => {
+    try {
+      const result = await invoke<AnalysisResult>('get_full_analysis')
+      set({ data: result })
+    } catch (e) {
+      console.error('刷新数据失败:', e)
+    }
+  },
+  
+  // 监听后台文件变化，增量更新已加载的数据
+  subscribeLiveUpdates: async () => {
+    const onChat = (event: { payload: ChatEvent }) => {
+      set({ watcherError: null })
+      const { data, deleting } = get()
+      if (!data || deleting) return
+      const next = applyChatEvent(data, event.payload)
+      if (next) {
+        set({ data: next })
+      } else {
+        get().refreshData()
+      }
+    }
+    
+    const unlisteners = await Promise.all([
+      listen<ChatEvent>('chat-added', onChat),
+      listen<ChatEvent>('chat-updated', onChat),
+      listen<StorageChange>('storage-changed', (event) => {
+        set({ watcherError: null })
+        const { data, deleting } = get()
+        if (!data || deleting) return
+        // 出现了尚未加载的工作区时整体刷新
+        const known = new Set(data.workspaces.map(w => w.id))
+        const hasUnknown = event.payload.changed_workspaces.some(id => !known.has(id))
+        set({ data: applyStorageChange(data, event.payload) })
+        if (hasUnknown) {
+          get().refreshData()
+        }
+      }),
+      listen<WatcherError>('watcher-error', (event) => {
+        set({ watcherError: event.payload.message })
+      }),
+    ])
+    return () => unlisteners.forEach(unlisten => unlisten())
+  },
+  
   // 加载垃圾桶
   loadTrash: async () => {
     try {
@@ -150,7 +207,7 @@ export const useAppStore = create<AppState>((set, get) => ({
       await invoke('delete_chat', { projectPath, chatId })
       await get().loadData()
     } catch (e) {
-      alert('删除失败: ' + String(e))
+      alert('删除失败: ' + formatError(e))
     } finally {
       set({ deleting: false, deleteProgress: null })
     }
@@ -163,7 +220,7 @@ export const useAppStore = create<AppState>((set, get) => ({
       await invoke('delete_chats_batch', { projectPath, chatIds })
       await get().loadData()
     } catch (e) {
-      alert('批量删除失败: ' + String(e))
+      alert('批量删除失败: ' + formatError(e))
     } finally {
       set({ deleting: false, deleteProgress: null })
     }
@@ -176,7 +233,7 @@ export const useAppStore = create<AppState>((set, get) => ({
       await invoke('delete_project_chats', { projectPath })
       await get().loadData()
     } catch (e) {
-      alert('删除失败: ' + String(e))
+      alert('删除失败: ' + formatError(e))
     } finally {
       set({ deleting: false, deleteProgress: null })
     }
@@ -195,7 +252,7 @@ export const useAppStore = create<AppState>((set, get) => ({
     for (let i = 0; i < projectPaths.length; i++) {
       const path = projectPaths[i]
       const project = data.projects.find(p => p.path === path)
-      const projectName = project?.name || path.split('/').pop() || 'Unknown'
+      const projectName = project?.name || basename(path) || 'Unknown'
       
       set({ 
         deleteProgress: { 
@@ -237,7 +294,7 @@ export const useAppStore = create<AppState>((set, get) => ({
       await invoke('delete_workspace_chats', { workspaceId })
       await get().loadData()
     } catch (e) {
-      alert('删除失败: ' + String(e))
+      alert('删除失败: ' + formatError(e))
     } finally {
       set({ deleting: false, deleteProgress: null })
     }
@@ -256,7 +313,7 @@ export const useAppStore = create<AppState>((set, get) => ({
     for (let i = 0; i < workspaceIds.length; i++) {
       const id = workspaceIds[i]
       const workspace = data.workspaces.find(w => w.id === id)
-      const workspaceName = workspace?.projects[0]?.split('/').pop() || id.slice(0, 8)
+      const workspaceName = (workspace?.projects[0] && basename(workspace.projects[0])) || id.slice(0, 8)
       
       set({ 
         deleteProgress: { 
@@ -304,7 +361,7 @@ export const useAppStore = create<AppState>((set, get) => ({
       await invoke('delete_trash_item', { trashId })
       await get().loadTrash()
     } catch (e) {
-      alert('删除失败: ' + String(e))
+      alert('删除失败: ' + formatError(e))
     }
   },
 }))
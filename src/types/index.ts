@@ -30,6 +30,38 @@ export interface DatabaseStats {
   checkpoint_size: number
   agent_kv_count: number
   agent_kv_size: number
+  item_table_prefixes: KeyPrefixStats[]
+  cursor_disk_kv_prefixes: KeyPrefixStats[]
+}
+
+// 按 key 前缀统计
+export interface KeySize {
+  size: number
+  key: string
+}
+
+export interface KeyPrefixStats {
+  prefix: string
+  count: number
+  total_size: number
+  total_size_human: string
+  avg_size: number
+  max_size: number
+  top_keys: KeySize[]
+}
+
+export interface TableKeyStats {
+  table: string
+  count: number
+  total_size: number
+  total_size_human: string
+  prefixes: KeyPrefixStats[]
+  top_keys: KeySize[]
+}
+
+export interface KeyBreakdown {
+  db_path: string
+  tables: TableKeyStats[]
 }
 
 // 聊天会话
@@ -39,6 +71,9 @@ export interface ChatSession {
   mode: 'agent' | 'chat' | string
   created_at: string | null
   updated_at: string | null
+  // 毫秒时间戳，排序使用
+  created_at_ms: number | null
+  updated_at_ms: number | null
   lines_added: number
   lines_removed: number
   files_changed: number
@@ -46,16 +81,30 @@ export interface ChatSession {
   branch: string
   is_archived: boolean
   subtitle: string
+  storage_size: number
 }
 
 // 项目统计
+// 工作区位置
+export type WorkspaceLocation =
+  | { kind: 'local' }
+  | { kind: 'ssh'; host: string }
+  | { kind: 'wsl'; distro: string }
+  | { kind: 'dev_container'; name: string }
+  | { kind: 'codespaces'; name: string }
+  | { kind: 'remote'; authority: string }
+
 export interface ProjectStats {
   name: string
   path: string
+  location: WorkspaceLocation
   chat_count: number
   lines_added: number
   lines_removed: number
   files_changed: number
+  storage_size: number
+  // key 为归一化后的模式（ChatMode）
+  modes: Record<string, ModeStats>
   chats: ChatSession[]
 }
 
@@ -68,8 +117,10 @@ export interface WorkspaceInfo {
   lines_added: number
   lines_removed: number
   files_changed: number
+  storage_size: number
   recent_chats: ChatSession[]
   is_multi_project: boolean
+  location: WorkspaceLocation
 }
 
 // 概览统计
@@ -81,7 +132,34 @@ export interface OverviewStats {
   net_lines: number
   total_files_changed: number
   agent_mode_count: number
+  // agent 以外所有模式的会话数，细分见 modes
   chat_mode_count: number
+  // key 为归一化后的模式（ChatMode）
+  modes: Record<string, ModeStats>
+  locations: LocationUsage[]
+}
+
+// 归一化后的会话模式
+export type ChatMode = 'agent' | 'chat' | 'edit' | 'plan' | 'background' | 'unknown' | 'other'
+
+export interface ModeStats {
+  kind: ChatMode
+  label: string
+  chat_count: number
+  lines_added: number
+  lines_removed: number
+  files_changed: number
+  // 归入这一模式的 unifiedMode 原始值及各自的会话数
+  raw_modes: Record<string, number>
+}
+
+export interface LocationUsage {
+  location: WorkspaceLocation
+  label: string
+  project_count: number
+  chat_count: number
+  lines_added: number
+  lines_removed: number
 }
 
 // 分析结果
@@ -91,6 +169,8 @@ export interface AnalysisResult {
   database: DatabaseStats
   projects: ProjectStats[]
   workspaces: WorkspaceInfo[]
+  // 长期统计记录失败的原因
+  usage_error: string | null
 }
 
 // 垃圾桶项目
@@ -104,7 +184,153 @@ export interface TrashItem {
   lines_removed: number
   files_changed: number
   deleted_at: string
+  deleted_at_ms: number
   original_data: string
+  workspace_id: string
+  kv_count: number
+  kv_size: number
+}
+
+// 垃圾桶恢复失败项
+export interface RestoreFailure {
+  trash_id: number
+  chat_name: string
+  error: string
+}
+
+// 垃圾桶恢复结果
+export interface RestoreResult {
+  restored_count: number
+  failures: RestoreFailure[]
+}
+
+// 后端结构化错误
+export interface AppError {
+  kind: string
+  message: string
+}
+
+// Cursor 运行状态
+export interface CursorStatus {
+  running: boolean
+  pids: number[]
+  db_locked: boolean
+  wal_active: boolean
+}
+
+// 排队中的删除请求
+export type DeletionRequest =
+  | { type: 'chats'; project_path: string; chat_ids: string[] }
+  | { type: 'project'; project_path: string }
+  | { type: 'workspace'; workspace_id: string }
+
+export interface PendingDeletion {
+  id: number
+  request: DeletionRequest
+  queued_at: string
+  last_error: string | null
+}
+
+// 数据库快照
+export interface Snapshot {
+  id: number
+  source_path: string
+  snapshot_path: string
+  created_at: string
+  size: number
+  reason: string
+}
+
+// 应用设置
+export interface Settings {
+  snapshot_generations: number
+  data_root: string | null
+  editor: string | null
+  timezone: string | null
+}
+
+// 显示时间使用的时区
+export interface TimezoneInfo {
+  configured: string | null
+  effective: string
+  utc_offset: string
+}
+
+// Cursor 数据目录
+export type DataRootSource = 'command_line' | 'environment' | 'settings' | 'default'
+
+export interface DataRootInfo {
+  path: string
+  source: DataRootSource
+  exists: boolean
+}
+
+// 编辑器配置
+export interface EditorProfile {
+  id: string
+  name: string
+  app_dir: string
+  process_names: string[]
+  chat_keys: string[]
+}
+
+export interface EditorSummary {
+  editor: EditorProfile
+  data_root: string
+  installed: boolean
+  active: boolean
+  storage: StorageInfo | null
+  overview: OverviewStats | null
+  error: string | null
+}
+
+// 会话全文
+export interface CodeBlock {
+  language: string
+  content: string
+  file_path: string | null
+}
+
+export interface ToolCall {
+  name: string
+  status: string
+  arguments: string | null
+  result: string | null
+}
+
+export interface ChatMessage {
+  bubble_id: string
+  role: 'user' | 'assistant' | 'unknown'
+  text: string
+  thinking: string | null
+  created_at: string | null
+  code_blocks: CodeBlock[]
+  tool_calls: ToolCall[]
+}
+
+export interface ChatTranscript {
+  chat_id: string
+  name: string | null
+  messages: ChatMessage[]
+}
+
+// 全文搜索
+export interface SearchFilters {
+  project_path?: string | null
+  mode?: string | null
+  limit?: number | null
+}
+
+export interface SearchHit {
+  chat_id: string
+  chat_name: string
+  project_path: string
+  workspace_id: string
+  mode: string
+  updated_at: string | null
+  updated_at_ms: number | null
+  snippet: string
+  rank: number
 }
 
 // Tab 类型
@@ -119,6 +345,7 @@ export type SortField =
   | 'updated_at' 
   | 'created_at' 
   | 'context_usage' 
+  | 'storage_size' 
   | 'name'
 
 export type SortOrder = 'asc' | 'desc'
@@ -129,4 +356,296 @@ export type ProjectSortField =
   | 'lines_removed' 
   | 'chat_count' 
   | 'files_changed' 
+  | 'storage_size' 
   | 'name'
+
+// 文件历史（User/History）
+export interface HistoryFile {
+  dir: string
+  resource: string
+  path: string
+  project_path: string
+  location: WorkspaceLocation
+  entry_count: number
+  total_size: number
+  oldest: string | null
+  newest: string | null
+  exists: boolean | null
+}
+
+export interface HistoryProject {
+  project_path: string
+  name: string
+  exists: boolean | null
+  file_count: number
+  missing_file_count: number
+  entry_count: number
+  total_size: number
+  total_size_human: string
+  oldest: string | null
+  newest: string | null
+}
+
+export interface HistoryReport {
+  total_size: number
+  total_size_human: string
+  file_count: number
+  entry_count: number
+  missing_file_count: number
+  missing_size: number
+  projects: HistoryProject[]
+  files: HistoryFile[]
+}
+
+// 文件历史清理
+export interface HistoryCleanupPolicy {
+  older_than_days?: number | null
+  keep_versions?: number | null
+  deleted_files?: boolean
+  deleted_projects?: boolean
+  dry_run?: boolean
+}
+
+export type RemovalReason = 'expired' | 'excess_version' | 'file_deleted' | 'project_deleted'
+
+export interface HistoryRemoval {
+  dir: string
+  path: string
+  entry_id: string
+  timestamp: string | null
+  size: number
+  reason: RemovalReason
+}
+
+export interface HistoryCleanupResult {
+  dry_run: boolean
+  batch_id: string | null
+  file_count: number
+  removed_count: number
+  bytes_saved: number
+  bytes_saved_human: string
+  removals: HistoryRemoval[]
+}
+
+export interface HistoryTrashBatch {
+  id: string
+  created_at: string
+  policy: HistoryCleanupPolicy
+  file_count: number
+  entry_count: number
+  size: number
+  size_human: string
+}
+
+// 孤立的 workspaceStorage 目录
+export type OrphanReason =
+  | 'folder_missing'
+  | 'workspace_file_missing'
+  | 'workspace_json_missing'
+  | 'workspace_json_invalid'
+
+export interface OrphanWorkspace {
+  id: string
+  reason: OrphanReason
+  target: string | null
+  size: number
+  size_human: string
+  chat_count: number
+  last_modified: string | null
+}
+
+export interface OrphanReport {
+  total_size: number
+  total_size_human: string
+  workspaces: OrphanWorkspace[]
+}
+
+export type OrphanAction = 'archive' | 'delete'
+
+export interface OrphanSkip {
+  id: string
+  error: string
+}
+
+export interface OrphanRemovalResult {
+  action: OrphanAction
+  removed: string[]
+  skipped: OrphanSkip[]
+  bytes_freed: number
+  bytes_freed_human: string
+  archive_dir: string | null
+}
+
+// state.vscdb 压缩
+export type CompactScope =
+  | { kind: 'global' }
+  | { kind: 'workspace'; workspace_id: string }
+  | { kind: 'workspaces' }
+  | { kind: 'all' }
+
+export interface DbCompaction {
+  db_path: string
+  label: string
+  size_before: number
+  size_after: number
+  reclaimable: number
+  bytes_saved: number
+  bytes_saved_human: string
+}
+
+export interface CompactionFailure {
+  db_path: string
+  label: string
+  error: string
+}
+
+export interface CompactionResult {
+  dry_run: boolean
+  databases: DbCompaction[]
+  failures: CompactionFailure[]
+  // Cursor 中途启动或数据库被占用后没有处理的库
+  skipped: CompactionFailure[]
+  size_before: number
+  size_after: number
+  bytes_saved: number
+  bytes_saved_human: string
+}
+
+// 全局库中的孤立会话数据
+export interface OrphanedComposer {
+  composer_id: string
+  name: string | null
+  has_composer_data: boolean
+  bubble_count: number
+  checkpoint_count: number
+  agent_kv_count: number
+  size: number
+  size_human: string
+}
+
+export interface KvOrphanReport {
+  known_composer_count: number
+  composer_data_count: number
+  composer_data_size: number
+  bubble_count: number
+  bubble_size: number
+  checkpoint_count: number
+  checkpoint_size: number
+  agent_kv_count: number
+  agent_kv_size: number
+  total_size: number
+  total_size_human: string
+  composers: OrphanedComposer[]
+}
+
+export interface KvPurgeResult {
+  purged_count: number
+  kv_count: number
+  bytes_freed: number
+  bytes_freed_human: string
+  trash_ids: number[]
+}
+
+// 文件监听事件（chat-added、chat-updated、storage-changed、watcher-error）
+export interface ChatEvent {
+  workspace_id: string
+  project_path: string | null
+  chat: ChatSession
+}
+
+export interface RemovedChat {
+  workspace_id: string
+  chat_id: string
+}
+
+export interface StorageChange {
+  changed_workspaces: string[]
+  removed_workspaces: string[]
+  removed_chats: RemovedChat[]
+  global_changed: boolean
+}
+
+export interface WatcherError {
+  message: string
+}
+
+// 长期统计
+export interface ModeTotals {
+  kind: ChatMode
+  label: string
+  chat_count: number
+  lines_added: number
+  lines_removed: number
+  raw_modes: Record<string, number>
+}
+
+export interface ProjectTotals {
+  project_path: string
+  chat_count: number
+  deleted_chat_count: number
+  lines_added: number
+  lines_removed: number
+  files_changed: number
+}
+
+export interface UsageTotals {
+  first_recorded_at: string | null
+  last_recorded_at: string | null
+  chat_count: number
+  deleted_chat_count: number
+  lines_added: number
+  lines_removed: number
+  files_changed: number
+  deleted_lines_added: number
+  deleted_lines_removed: number
+  avg_context_usage: number
+  modes: ModeTotals[]
+  projects: ProjectTotals[]
+}
+
+export interface DailyStorage {
+  day: string
+  total_size: number
+  global_storage_size: number
+  workspace_storage_size: number
+  history_size: number
+  state_vscdb_size: number
+  chat_count: number
+  lines_added: number
+  lines_removed: number
+  recorded_at: string
+}
+
+export interface RecordResult {
+  day: string
+  chat_count: number
+  new_chat_count: number
+  deleted_chat_count: number
+}
+
+// 活动时间线
+export type Granularity = 'day' | 'week' | 'month'
+
+export interface ActivityRange {
+  start: string | null
+  end: string | null
+}
+
+export interface ActivityBucket {
+  start: string
+  label: string
+  chat_count: number
+  agent_count: number
+  chat_mode_count: number
+  lines_added: number
+  lines_removed: number
+  files_changed: number
+}
+
+export interface ActivityTimeline {
+  granularity: Granularity
+  start: string | null
+  end: string | null
+  buckets: ActivityBucket[]
+  undated_count: number
+}